@@ -0,0 +1,281 @@
+use num::{BigUint, Zero, One};
+use crate::code::{
+    builder::{CircuitBuilder, Var, Lc, MODULUS},
+    comparator::less,
+    logic::{not, pack},
+};
+
+// arithmetic over the field, and over integers held in bits. the gadgets that need a value the circuit cannot
+// compute, an inverse or a quotient, declare it as a hint and check it. each has a helper below working out
+// its hints from the same inputs; call them in the order the gadgets were called and hand the hints over
+// with Knowledge::with_hints.
+
+pub fn sub<A: Into<Lc>, B: Into<Lc>>(a: A, b: B) -> Lc {
+    a.into() - b
+}
+
+// constrains a = b. unlike CircuitBuilder::enforce_equal, which reassigns its variable, neither side is changed
+// and either may be any combination.
+pub fn enforce_equal<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) {
+    builder.enforce_zero(a.into() - b, 1)
+}
+
+// 1 when x is 0, 0 otherwise. with out = 1 - x·inv and x·out = 0, out can only be 0 when inv is the inverse
+// of x, and x can only be nonzero when out is 0, whatever the prover hints.
+pub fn is_zero<X: Into<Lc>>(builder: &mut CircuitBuilder, x: X) -> Lc {
+    let x = x.into();
+    let inv = builder.hint();
+    let x_inv = builder.mul(x.clone(), inv);
+    let out = not(x_inv);
+    builder.enforce_zero(x, out.clone());
+    out
+}
+
+// 1 when a = b, 0 otherwise.
+pub fn equal<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    is_zero(builder, a.into() - b)
+}
+
+// the inverse of x in the field. an x of 0 cannot be proved.
+pub fn inverse<X: Into<Lc>>(builder: &mut CircuitBuilder, x: X) -> Var {
+    let inv = builder.hint();
+    let one = builder.mul(x, inv);
+    builder.enforce_zero(one - 1, 1);
+    inv
+}
+
+// the integer quotient and remainder, a = q·b + r with r < b, for b given as its width bits and a below
+// 2^(2·width). q is hinted as 2·width bits, so it fits whatever the divisor, and r as width bits; q·b + r then stays
+// well below the modulus, which is why width is at most 64. a b of 0 cannot be proved.
+pub fn divide<A, B>(builder: &mut CircuitBuilder, a: A, b: &[B]) -> (Lc, Lc)
+where
+    A: Into<Lc>,
+    B: Into<Lc> + Clone,
+{
+    let width = b.len();
+    assert!(width <= 64, "arith::divide() dividing by a {} bit number, at most 64 are allowed", width);
+    let (q, r) = (builder.hint_bits(2 * width), builder.hint_bits(width));
+    let qb = builder.mul(pack(&q), pack(b));
+    enforce_equal(builder, Lc::from(qb) + pack(&r), a);
+    let below = less(builder, &r, b);
+    enforce_equal(builder, below, 1);
+    (pack(&q), pack(&r))
+}
+
+// a mod m for a constant m below 2^width and a below 2^(2·width), q hinted as for divide. as m is constant,
+// q·m + r = a needs no product.
+pub fn modulo<A: Into<Lc>>(builder: &mut CircuitBuilder, a: A, m: u64, width: usize) -> Lc {
+    assert!(width <= 64, "arith::modulo() reducing into {} bits, at most 64 are allowed", width);
+    assert!(m > 0 && (width == 64 || m >> width == 0), "arith::modulo() {} is not a modulus of {} bits", m, width);
+    let (q, r) = (builder.hint_bits(2 * width), builder.hint_bits(width));
+    let m_bits: Vec<Lc> = (0..width).map(|i| Lc::constant((m >> i) & 1)).collect();
+    enforce_equal(builder, pack(&q).scale(m) + pack(&r), a);
+    let below = less(builder, &r, &m_bits);
+    enforce_equal(builder, below, 1);
+    pack(&r)
+}
+
+fn modulus() -> BigUint {
+    MODULUS.parse::<BigUint>().expect("arith::modulus() parsing the scalar field modulus")
+}
+
+fn bits(x: &BigUint, width: usize) -> Vec<BigUint> {
+    (0..width).map(|i| (x >> i) & BigUint::one()).collect()
+}
+
+// x^(r - 2), the inverse for any x but 0, which has none and gets 0.
+fn invert(x: &BigUint) -> BigUint {
+    let r = modulus();
+    x.modpow(&(&r - 2u32), &r)
+}
+
+pub fn is_zero_hint(x: &BigUint) -> Vec<BigUint> {
+    vec![invert(x)]
+}
+
+pub fn equal_hint(a: &BigUint, b: &BigUint) -> Vec<BigUint> {
+    let r = modulus();
+    is_zero_hint(&((a % &r + &r - b % &r) % &r))
+}
+
+pub fn inverse_hint(x: &BigUint) -> Vec<BigUint> {
+    vec![invert(x)]
+}
+
+// fails where divide could not be proved: a b of 0 or wider than width bits, or a quotient wider than 2·width.
+pub fn divide_hint(a: &BigUint, b: &BigUint, width: usize) -> Result<Vec<BigUint>, String> {
+    if b.is_zero() {
+        return Err(format!("dividing {} by 0", a))
+    }
+    if b.bits() > width {
+        return Err(format!("the divisor {} does not fit in {} bits", b, width))
+    }
+    let q = a / b;
+    if q.bits() > 2 * width {
+        return Err(format!("the quotient of {} by {} does not fit in {} bits", a, b, 2 * width))
+    }
+    let mut hints = bits(&q, 2 * width);
+    hints.extend(bits(&(a % b), width));
+    Ok(hints)
+}
+
+pub fn modulo_hint(a: &BigUint, m: u64, width: usize) -> Result<Vec<BigUint>, String> {
+    divide_hint(a, &BigUint::from(m), width)
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::groth16::fr::{FrLocal, G1Local, G2Local};
+    use num::BigUint;
+    use crate::{
+        code::{
+            builder::CircuitBuilder,
+            arith::{self, sub, is_zero, equal, inverse, divide, modulo},
+        },
+        common::CommonReference,
+        crypto::Algorithm,
+        interface::{Andromeda, GoZero, MarkZero},
+        knowledge::Knowledge,
+    };
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    #[test]
+    fn test_arith_gadgets() {
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness("x");
+        let y = builder.input("y");
+        let (a, _) = builder.witness_number("a", 8);
+        let (_, b) = builder.input_number("b", 8);
+        let zero = is_zero(&mut builder, x);
+        builder.output("zero", zero);
+        let same = equal(&mut builder, x, y);
+        builder.output("same", same);
+        // the inverse of the inverse comes back to y.
+        let inv = inverse(&mut builder, y);
+        let back = inverse(&mut builder, inv);
+        builder.output("back", back);
+        let (q, r) = divide(&mut builder, a, &b);
+        builder.output("q", q);
+        builder.output("r", r.clone());
+        let m = modulo(&mut builder, a, 7, 8);
+        builder.output("m", m);
+        builder.output("diff", sub(a, r));
+        let crs: Crs = builder.reference();
+
+        let n = |x: usize| BigUint::from(x);
+        let hints = |x: usize, y: usize, a: usize, b: usize| {
+            let mut hints = arith::is_zero_hint(&n(x));
+            hints.extend(arith::equal_hint(&n(x), &n(y)));
+            let inv = arith::inverse_hint(&n(y));
+            hints.extend(inv.clone());
+            hints.extend(arith::inverse_hint(&inv[0]));
+            hints.extend(arith::divide_hint(&n(a), &n(b), 8).unwrap());
+            hints.extend(arith::modulo_hint(&n(a), 7, 8).unwrap());
+            hints
+        };
+        let prove = |x: usize, y: usize, a: usize, b: usize, hints: Vec<BigUint>, out: Vec<usize>| Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into(Some(vec![a]), Some(vec![b]), Some(vec![x, a]), Some(vec![y, b]), Some(String::from("u8")))
+                .with_hints(hints),
+            Some(vec![y, b].into_iter().chain(out).collect()),
+            Some(vec![b]),
+            Some(String::from("u8")),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        // x, y, a, b, then zero, same, back, q, r, m and diff.
+        let cases = vec![
+            ((5, 3, 200, 7), vec![0, 0, 3, 28, 4, 4, 196]),
+            ((4, 4, 6, 7), vec![0, 1, 4, 0, 6, 6, 0]),
+            ((0, 1, 255, 1), vec![1, 0, 1, 255, 0, 3, 255]),
+            ((0, 0xff, 255, 255), vec![1, 0, 255, 1, 0, 3, 255]),
+        ];
+        for ((x, y, a, b), out) in cases {
+            assert!(prove(x, y, a, b, hints(x, y, a, b), out.clone()));
+            for i in 0..out.len() {
+                let mut wrong = out.clone();
+                wrong[i] += 1;
+                assert!(!prove(x, y, a, b, hints(x, y, a, b), wrong));
+            }
+        }
+    }
+
+    #[test]
+    fn test_arith_hints() {
+        // a hint that lies is caught: x = 5 with no inverse claims to be zero.
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness("x");
+        let zero = is_zero(&mut builder, x);
+        builder.output("zero", zero);
+        let crs: Crs = builder.reference();
+        let prove = |x: usize, hint: usize, zero: usize| Andromeda::into(
+            crs.clone(),
+            Knowledge::into_num_only(Some(vec![x]), None).with_hints(vec![BigUint::from(hint)]),
+            Some(vec![zero]),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        assert!(prove(0, 0, 1));
+        assert!(prove(0, 9, 1));
+        assert!(!prove(5, 0, 1));
+        assert!(!prove(5, 0, 0));
+
+        // nor can a remainder as large as the divisor, or a divisor of 0, be proved.
+        let mut builder = CircuitBuilder::new();
+        let (a, _) = builder.witness_number("a", 8);
+        let (_, b) = builder.input_number("b", 8);
+        let (q, _) = divide(&mut builder, a, &b);
+        builder.output("q", q);
+        let crs: Crs = builder.reference();
+        let prove = |a: usize, b: usize, q: usize, r: usize| {
+            let hints = (0..16).map(|i| (q >> i) & 1).chain((0..8).map(|i| (r >> i) & 1)).map(BigUint::from).collect();
+            Andromeda::into_bits(
+                crs.clone(),
+                Knowledge::into(Some(vec![a]), Some(vec![b]), Some(vec![a]), Some(vec![b]), Some(String::from("u8")))
+                    .with_hints(hints),
+                Some(vec![b, q]),
+                Some(vec![b]),
+                Some(String::from("u8")),
+                Algorithm::Ed25519.init_key_pair()
+            ).go().verify()
+        };
+        assert!(prove(20, 6, 3, 2));
+        assert!(!prove(20, 6, 2, 8));
+        assert!(!prove(0, 0, 0, 0));
+        assert!(!prove(0, 0, 5, 0));
+
+        // a up to 2^16 divides by an 8 bit b however small, the quotient hinted wide enough to hold it.
+        let mut builder = CircuitBuilder::new();
+        let a = builder.witness("a");
+        let (_, b) = builder.input_number("b", 8);
+        let (q, r) = divide(&mut builder, a, &b);
+        builder.output("q", q);
+        builder.output("r", r);
+        let m = modulo(&mut builder, a, 3, 8);
+        builder.output("m", m);
+        let crs: Crs = builder.reference();
+        let n = |x: usize| BigUint::from(x);
+        let prove = |a: usize, b: usize, out: Vec<usize>| {
+            let mut hints = arith::divide_hint(&n(a), &n(b), 8).unwrap();
+            hints.extend(arith::modulo_hint(&n(a), 3, 8).unwrap());
+            Andromeda::into_bits(
+                crs.clone(),
+                Knowledge::into(None, Some(vec![b]), Some(vec![a]), Some(vec![b]), Some(String::from("u8")))
+                    .with_hints(hints),
+                Some(vec![b].into_iter().chain(out).collect()),
+                Some(vec![b]),
+                Some(String::from("u8")),
+                Algorithm::Ed25519.init_key_pair()
+            ).go().verify()
+        };
+        assert!(prove(1000, 3, vec![333, 1, 1]));
+        assert!(prove(65535, 1, vec![65535, 0, 0]));
+        assert!(!prove(1000, 3, vec![334, 1, 1]));
+
+        // and the helpers refuse what divide could not prove.
+        assert!(arith::divide_hint(&n(5), &n(0), 8).is_err());
+        assert!(arith::divide_hint(&n(5), &n(256), 8).is_err());
+        assert!(arith::divide_hint(&n(65536), &n(1), 8).is_err());
+        assert!(arith::modulo_hint(&n(65536), 3, 8).is_ok());
+        assert_eq!(24, arith::modulo_hint(&n(1000), 3, 8).unwrap().len());
+    }
+}
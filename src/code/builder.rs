@@ -0,0 +1,545 @@
+use num::{BigUint, Zero, One};
+use std::{
+    fmt::Write,
+    ops::{Add, Sub, Neg, Mul},
+};
+use crate::{
+    code::logic::pack_signed,
+    common::Common,
+};
+
+// the scalar field of FrLocal, BN254's r. coefficients are reduced into it, so -1 renders as r - 1.
+pub const MODULUS: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+thread_local! {
+    static R: BigUint = MODULUS.parse::<BigUint>().expect("builder::R parsing the scalar field modulus");
+}
+
+fn reduce(c: BigUint) -> BigUint {
+    R.with(|r| c % r)
+}
+
+fn negate(c: &BigUint) -> BigUint {
+    R.with(|r| (r - c % r) % r)
+}
+
+// a variable of the circuit it was declared in. every handle the builder gives out is already assigned,
+// so any of them can be used on either side of a product.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+// a linear combination of variables plus a constant, which is all .zk allows on either side of a product.
+// None stands for the constant one.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Lc(Vec<(Option<Var>, BigUint)>);
+
+impl Lc {
+    pub fn zero() -> Self {
+        Lc(Vec::new())
+    }
+
+    pub fn constant<C: Into<BigUint>>(c: C) -> Self {
+        Lc(vec![(None, reduce(c.into()))]).normal()
+    }
+
+    pub fn scale<C: Into<BigUint>>(self, c: C) -> Self {
+        let c = c.into();
+        Lc(self.0.into_iter().map(|(v, x)| (v, reduce(x * &c))).collect()).normal()
+    }
+
+    // the constant, when there are no variables.
+    pub fn as_constant(&self) -> Option<BigUint> {
+        match self.0.iter().all(|(v, _)| v.is_none()) {
+            true => Some(self.0.first().map_or(BigUint::zero(), |(_, c)| c.clone())),
+            false => None,
+        }
+    }
+
+    // like terms merged and zero terms dropped, so equal combinations compare equal.
+    fn normal(self) -> Self {
+        let mut terms: Vec<(Option<Var>, BigUint)> = Vec::new();
+        for (v, c) in self.0 {
+            match terms.iter_mut().find(|(w, _)| *w == v) {
+                Some(term) => term.1 = reduce(&term.1 + c),
+                None => terms.push((v, c)),
+            }
+        }
+        terms.retain(|(_, c)| !c.is_zero());
+        Lc(terms)
+    }
+}
+
+impl From<Var> for Lc {
+    fn from(v: Var) -> Self {
+        Lc(vec![(Some(v), BigUint::one())])
+    }
+}
+
+impl From<usize> for Lc {
+    fn from(c: usize) -> Self {
+        Lc::constant(c)
+    }
+}
+
+impl From<BigUint> for Lc {
+    fn from(c: BigUint) -> Self {
+        Lc::constant(c)
+    }
+}
+
+impl<L: Into<Lc>> Add<L> for Lc {
+    type Output = Lc;
+    fn add(mut self, o: L) -> Lc {
+        self.0.extend(o.into().0);
+        self.normal()
+    }
+}
+
+impl<L: Into<Lc>> Sub<L> for Lc {
+    type Output = Lc;
+    fn sub(self, o: L) -> Lc {
+        self + -o.into()
+    }
+}
+
+impl Neg for Lc {
+    type Output = Lc;
+    fn neg(self) -> Lc {
+        Lc(self.0.into_iter().map(|(v, c)| (v, negate(&c))).collect()).normal()
+    }
+}
+
+impl Mul<usize> for Lc {
+    type Output = Lc;
+    fn mul(self, c: usize) -> Lc {
+        self.scale(c)
+    }
+}
+
+impl<L: Into<Lc>> Add<L> for Var {
+    type Output = Lc;
+    fn add(self, o: L) -> Lc {
+        Lc::from(self) + o
+    }
+}
+
+impl<L: Into<Lc>> Sub<L> for Var {
+    type Output = Lc;
+    fn sub(self, o: L) -> Lc {
+        Lc::from(self) - o
+    }
+}
+
+impl Neg for Var {
+    type Output = Lc;
+    fn neg(self) -> Lc {
+        -Lc::from(self)
+    }
+}
+
+impl Mul<usize> for Var {
+    type Output = Lc;
+    fn mul(self, c: usize) -> Lc {
+        Lc::from(self).scale(c)
+    }
+}
+
+// who assigns a variable and who sees it. the prover assigns the bits and inputs, in the order Knowledge
+// lays them out: private bits, public bits, private nums, public nums, private signed nums, public signed nums,
+// hints. the verifier is handed the public nums and outputs in the order they were declared, then the public bits,
+// then the public signed nums, then the outputs held as field elements, as Marker lays them out.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Role {
+    WitnessBit,
+    PublicBit,
+    Witness,
+    Public,
+    SignedWitness,
+    SignedPublic,
+    Hint,
+    Output,
+    Element,
+    Internal,
+}
+
+// the roles of (in ...), in the order the prover assigns them.
+const IN: &[Role] = &[
+    Role::WitnessBit, 
+    Role::PublicBit, 
+    Role::Witness, 
+    Role::Public, 
+    Role::SignedWitness, 
+    Role::SignedPublic, 
+    Role::Hint,
+];
+
+// builds a .zk program one constraint at a time. each line of the program assigns its left side the
+// product of two linear combinations, and assigning a variable that already has a value is how a
+// constraint is enforced: the prover's two values for it must agree.
+// names are checked as they are declared and a bad or repeated one panics, as it is a mistake in the circuit.
+#[derive(Clone, Debug, Default)]
+pub struct CircuitBuilder {
+    names: Vec<String>,
+    roles: Vec<Role>,
+    lines: Vec<(Var, Lc, Lc)>,
+    zero: Option<Var>,
+}
+
+impl CircuitBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // a private num, the prover's alone.
+    pub fn witness(&mut self, name: &str) -> Var {
+        self.declare(name, Role::Witness)
+    }
+
+    // a public num, assigned by the prover and checked by the verifier.
+    pub fn input(&mut self, name: &str) -> Var {
+        self.declare(name, Role::Public)
+    }
+
+    // width private bits named name0, name1 and on, least significant first as Knowledge expands them.
+    // width should match the tag the Knowledge is built with. every bit is constrained to be 0 or 1.
+    pub fn witness_bits(&mut self, name: &str, width: usize) -> Vec<Var> {
+        self.bits(name, width, Role::WitnessBit)
+    }
+
+    pub fn input_bits(&mut self, name: &str, width: usize) -> Vec<Var> {
+        self.bits(name, width, Role::PublicBit)
+    }
+
+    // a public result of the circuit, equal to a.
+    pub fn output<A: Into<Lc>>(&mut self, name: &str, a: A) -> Var {
+        let v = self.declare(name, Role::Output);
+        self.lines.push((v, a.into(), Lc::constant(1u32)));
+        v
+    }
+
+    // a public result handed to the verifier as a field element rather than a num, for a value such as a digest
+    // that no usize can carry. Andromeda::with_elements hands them over after every other public value.
+    pub fn output_element<A: Into<Lc>>(&mut self, name: &str, a: A) -> Var {
+        let v = self.declare(name, Role::Element);
+        self.lines.push((v, a.into(), Lc::constant(1u32)));
+        v
+    }
+
+    // a private field element the prover works out from the other values, such as an inverse, which the
+    // circuit then checks. hints are handed over with Knowledge::with_hints in the order they are declared.
+    pub fn hint(&mut self) -> Var {
+        let name = format!("_{}", self.names.len());
+        self.push(name, Role::Hint)
+    }
+
+    // width hints constrained to be bits, for a value the prover has to show fits in width bits.
+    pub fn hint_bits(&mut self, width: usize) -> Vec<Var> {
+        let bits: Vec<Var> = (0..width).map(|_| self.hint()).collect();
+        for &b in bits.iter() {
+            self.boolean(b);
+        }
+        bits
+    }
+
+    // a private num together with its width bits, constrained to be its binary expansion, so the same value
+    // can be compared bit by bit and used in arithmetic. Knowledge::into_linked hands over both from one number.
+    pub fn witness_number(&mut self, name: &str, width: usize) -> (Var, Vec<Var>) {
+        let bits = self.witness_bits(name, width);
+        let n = self.witness(name);
+        self.enforce_bits(n, &bits);
+        (n, bits)
+    }
+
+    pub fn input_number(&mut self, name: &str, width: usize) -> (Var, Vec<Var>) {
+        let bits = self.input_bits(name, width);
+        let n = self.input(name);
+        self.enforce_bits(n, &bits);
+        (n, bits)
+    }
+
+    // a private signed num together with its width two's complement bits, constrained so that pack_signed of the
+    // bits is the num. a negative num is its additive inverse in the field, as IntoField gives a Vec<isize>, and
+    // Knowledge::into_signed hands over both from one isize.
+    pub fn witness_signed(&mut self, name: &str, width: usize) -> (Var, Vec<Var>) {
+        let bits = self.witness_bits(name, width);
+        let n = self.declare(name, Role::SignedWitness);
+        self.enforce_zero(pack_signed(&bits) - n, 1);
+        (n, bits)
+    }
+
+    pub fn input_signed(&mut self, name: &str, width: usize) -> (Var, Vec<Var>) {
+        let bits = self.input_bits(name, width);
+        let n = self.declare(name, Role::SignedPublic);
+        self.enforce_zero(pack_signed(&bits) - n, 1);
+        (n, bits)
+    }
+
+    // constrains n = sum(bits[i] · 2^i), with bits least significant first. enforce_equal would reassign n the sum,
+    // quietly dropping the number the prover gave, so the difference is held to zero instead and a mismatch fails.
+    pub fn enforce_bits(&mut self, n: Var, bits: &[Var]) {
+        let sum = bits.iter()
+            .enumerate()
+            .fold(Lc::zero(), |sum, (i, &b)| sum + Lc::from(b).scale(BigUint::one() << i));
+        self.enforce_zero(sum - n, 1)
+    }
+
+    // a fresh variable holding a · b.
+    pub fn mul<A: Into<Lc>, B: Into<Lc>>(&mut self, a: A, b: B) -> Var {
+        let v = self.fresh();
+        self.lines.push((v, a.into(), b.into()));
+        v
+    }
+
+    // a fresh variable holding a, for when a combination is needed as a single variable.
+    pub fn assign<A: Into<Lc>>(&mut self, a: A) -> Var {
+        self.mul(a, 1)
+    }
+
+    // constrains a · b = c, for a variable c that already holds its value.
+    pub fn enforce<A: Into<Lc>, B: Into<Lc>>(&mut self, a: A, b: B, c: Var) {
+        self.lines.push((c, a.into(), b.into()));
+    }
+
+    pub fn enforce_equal<A: Into<Lc>>(&mut self, c: Var, a: A) {
+        self.enforce(a, 1, c)
+    }
+
+    // constrains a · b = 0. every such product is assigned to one shared variable that is also assigned 0 · 1,
+    // and as each line is checked against the variable's last value, all of them must be zero.
+    pub fn enforce_zero<A: Into<Lc>, B: Into<Lc>>(&mut self, a: A, b: B) {
+        let zero = match self.zero {
+            Some(zero) => zero,
+            None => {
+                let zero = self.mul(0, 1);
+                self.zero = Some(zero);
+                zero
+            },
+        };
+        self.enforce(a, b, zero)
+    }
+
+    // constrains b to 0 or 1 as b · (1 - b) = 0. a prover could otherwise put 2, or -1, where a bit belongs.
+    pub fn boolean(&mut self, b: Var) {
+        self.enforce_zero(b, Lc::constant(1u32) - b)
+    }
+
+    pub fn name(&self, v: Var) -> &str {
+        &self.names[v.0]
+    }
+
+    // the number of values the prover assigns and the verifier is handed.
+    pub fn inputs_len(&self) -> usize {
+        self.with_roles(IN).len()
+    }
+    pub fn public_len(&self) -> usize {
+        self.public().len()
+    }
+
+    pub fn render(&self) -> String {
+        let list = |head: &str, vars: Vec<Var>| vars.into_iter().fold(format!("({}", head), |acc, v| acc + " " + self.name(v)) + ")\n";
+        let mut code = list("in", self.with_roles(IN));
+        code.push_str(&list("out", self.with_roles(&[Role::Output, Role::Element])));
+        code.push_str(&list("verify", self.public()));
+        code.push_str("(program\n");
+        for (v, a, b) in self.lines.iter() {
+            writeln!(code, "    (= {} (* {} {}))", self.name(*v), self.factor(a), self.factor(b))
+                .expect("CircuitBuilder::render() writing to a String");
+        }
+        code.push_str(")\n");
+        code
+    }
+
+    // runs the program on inputs, given in the order of (in ...), as the prover does: each line assigns its left
+    // side in turn, then every line must hold for the final values. gives the values of (verify ...), or None
+    // when a constraint fails or there is not exactly one input for each of (in ...), without the cost of a proof.
+    pub fn evaluate(&self, inputs: &[BigUint]) -> Option<Vec<BigUint>> {
+        let order = self.with_roles(IN);
+        if order.len() != inputs.len() {
+            return None
+        }
+        let mut values: Vec<Option<BigUint>> = vec![None; self.names.len()];
+        for (v, x) in order.iter().zip(inputs.iter()) {
+            values[v.0] = Some(reduce(x.clone()));
+        }
+        let eval = |lc: &Lc, values: &Vec<Option<BigUint>>| lc.0.iter().fold(BigUint::zero(), |acc, (v, c)| match v {
+            Some(v) => acc + c * values[v.0].as_ref().expect("CircuitBuilder::evaluate() a variable used before it is assigned"),
+            None => acc + c,
+        });
+        for (v, a, b) in self.lines.iter() {
+            values[v.0] = Some(reduce(eval(a, &values) * eval(b, &values)));
+        }
+        let holds = self.lines.iter()
+            .all(|(v, a, b)| values[v.0] == Some(reduce(eval(a, &values) * eval(b, &values))));
+        match holds {
+            true => Some(self.public().iter().map(|v| values[v.0].clone().unwrap_or_else(BigUint::zero)).collect()),
+            false => None,
+        }
+    }
+
+    pub fn reference<C, T, U, V>(&self) -> C
+    where
+        C: Common<T, U, V>,
+    {
+        C::new(self.render())
+    }
+
+    fn public(&self) -> Vec<Var> {
+        self.with_roles(&[Role::Public, Role::Output, Role::PublicBit, Role::SignedPublic, Role::Element])
+    }
+
+    // the variables holding any of roles, grouped in the order the roles are given.
+    fn with_roles(&self, roles: &[Role]) -> Vec<Var> {
+        roles.iter()
+            .flat_map(|role| (0..self.names.len()).filter(move |&i| self.roles[i] == *role))
+            .map(Var)
+            .collect()
+    }
+
+    // a single variable or constant stands alone, anything else is a sum of terms.
+    fn factor(&self, lc: &Lc) -> String {
+        let term = |(v, c): &(Option<Var>, BigUint)| match (v, c.is_one()) {
+            (None, _) => c.to_string(),
+            (Some(v), true) => self.name(*v).to_string(),
+            (Some(v), false) => format!("(* {} {})", c, self.name(*v)),
+        };
+        match lc.0.as_slice() {
+            [] => String::from("0"),
+            [(None, c)] => c.to_string(),
+            [(Some(v), c)] if c.is_one() => self.name(*v).to_string(),
+            terms => terms.iter().map(term).fold(String::from("(+"), |acc, t| acc + " " + &t) + ")",
+        }
+    }
+
+    fn bits(&mut self, name: &str, width: usize, role: Role) -> Vec<Var> {
+        let bits: Vec<Var> = (0..width).map(|i| self.declare(&format!("{}{}", name, i), role)).collect();
+        for &b in bits.iter() {
+            self.boolean(b);
+        }
+        bits
+    }
+
+    fn declare(&mut self, name: &str, role: Role) -> Var {
+        let valid = name.chars().next().map_or(false, |c| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            panic!("CircuitBuilder::declare() {:?} is not a variable name, start with a letter", name)
+        }
+        if self.names.iter().any(|n| n == name) {
+            panic!("CircuitBuilder::declare() {} is declared twice", name)
+        }
+        self.push(name.to_string(), role)
+    }
+
+    // internal names start with an underscore, which declared names cannot.
+    fn fresh(&mut self) -> Var {
+        let name = format!("_{}", self.names.len());
+        self.push(name, Role::Internal)
+    }
+
+    fn push(&mut self, name: String, role: Role) -> Var {
+        self.names.push(name);
+        self.roles.push(role);
+        Var(self.names.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::{
+        CoefficientPoly,
+        groth16,
+        groth16::fr::{FrLocal, G1Local, G2Local},
+    };
+    use num::BigUint;
+    use std::str::FromStr;
+    use crate::{
+        code::builder::{CircuitBuilder, Lc, MODULUS},
+        common::{CommonReference, Common},
+        crypto::Algorithm,
+        interface::{Andromeda, GoZero, MarkZero},
+        knowledge::Knowledge,
+    };
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    #[test]
+    fn test_circuit_builder() {
+        // z = x·y - x + 3, with x private and y public.
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness("x");
+        let y = builder.input("y");
+        let xy = builder.mul(x, y);
+        let z = builder.output("z", xy - x + 3);
+        assert_eq!("z", builder.name(z));
+        assert_eq!((2, 2), (builder.inputs_len(), builder.public_len()));
+        let minus_one = MODULUS.parse::<BigUint>().unwrap() - 1u32;
+        assert_eq!(
+            format!("(in x y)\n(out z)\n(verify y z)\n(program\n    (= _2 (* x y))\n    (= z (* (+ _2 (* {} x) 3) 1))\n)\n", minus_one),
+            builder.render()
+        );
+        assert_eq!(Lc::zero(), Lc::from(x) - x);
+        assert_eq!(Some(BigUint::from(5u32)), (Lc::from(2) + 3).as_constant());
+
+        let crs: Crs = builder.reference();
+        let prove = |x: usize, y: usize, z: usize| Andromeda::into(
+            crs.clone(),
+            Knowledge::into_num_only(Some(vec![x]), Some(vec![y])),
+            Some(vec![y, z]),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        assert!(prove(5, 7, 33));
+        assert!(!prove(5, 7, 34));
+        assert!(!prove(5, 8, 33));
+        let n = |x: usize| BigUint::from(x);
+        assert_eq!(Some(vec![n(7), n(33)]), builder.evaluate(&[n(5), n(7)]));
+        // too few or too many inputs give nothing rather than a panic.
+        assert_eq!(None, builder.evaluate(&[n(5)]));
+        assert_eq!(None, builder.evaluate(&[n(5), n(7), n(9)]));
+
+        // an enforced constraint the witness breaks cannot be proved.
+        builder.enforce(x, x, xy);
+        let crs: Crs = builder.reference();
+        let prove = |x: usize, y: usize, z: usize| Andromeda::into(
+            crs.clone(),
+            Knowledge::into_num_only(Some(vec![x]), Some(vec![y])),
+            Some(vec![y, z]),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        assert!(prove(5, 5, 23));
+        assert!(!prove(5, 7, 33));
+    }
+
+    #[test]
+    fn test_circuit_builder_bits() {
+        // n = x0 + 2·x1, so x = [2, 0] would pass for n = 2 and x = [-1, 1] for n = 1 were the bits not constrained.
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness_bits("x", 2);
+        builder.output("n", x[0] + x[1] * 2);
+        let crs: Crs = builder.reference();
+        let minus_one = FrLocal::from_str(&(MODULUS.parse::<BigUint>().unwrap() - 1u32).to_string()).unwrap();
+        // proves with any field values at all, which Knowledge would never hand over.
+        let prove = |x: Vec<FrLocal>, n: FrLocal| {
+            let (code, qap, sg1, sg2) = crs.clone().get();
+            let weights = groth16::weights(&code, &x).unwrap();
+            let proof = groth16::prove(&qap, (&sg1, &sg2), &weights);
+            groth16::verify::<CoefficientPoly<FrLocal>, _, _, _, _>((sg1, sg2), &[n], proof)
+        };
+        let n = FrLocal::from;
+        assert!(prove(vec![n(0), n(1)], n(2)));
+        assert!(prove(vec![n(1), n(1)], n(3)));
+        assert!(!prove(vec![n(2), n(0)], n(2)));
+        assert!(!prove(vec![minus_one, n(1)], n(1)));
+        assert!(!prove(vec![n(0), n(2)], n(4)));
+        let n = |x: u32| BigUint::from(x);
+        assert_eq!(Some(vec![n(2)]), builder.evaluate(&[n(0), n(1)]));
+        assert_eq!(None, builder.evaluate(&[n(2), n(0)]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_circuit_builder_names() {
+        let mut builder = CircuitBuilder::new();
+        builder.witness("x");
+        builder.input("x");
+    }
+}
@@ -1,222 +1,622 @@
-use std::fs::File;
-use std::io::{LineWriter, Write};
-use std::path::Path;
-
-// generates a new file containing a comparator.
-// tag is used to identify the bits we desire, RANGE generates an a < b < c and COMP produces a < b
-pub fn new(tag: &'static str, path: &Path) {
-    let file = LineWriter::new(
-        File::create(path).unwrap()
-    );
-    match tag {
-        "64 RANGE" => build(3, 64, file),
-        "32 RANGE" => build(3, 32, file),
-        "16 RANGE" => build(3, 16, file),
-        "8 RANGE" => build(3, 8, file),
-        "64 COMP" => build(3, 64, file),
-        "32 COMP" => build(2, 32, file),
-        "16 COMP" => build(2, 16, file),
-        "8 COMP" => build(2, 8, file),
-        &_ => panic!("bad tag"),
-    }
-}
-
-fn build(quantity: usize, bits: usize, mut file: LineWriter<File>) {
-
-    file.write_all(b"(").unwrap();
-        file.write_all(b"in").unwrap();
-            for num in 0..quantity {
-                if num == 0 {
-                    for a in 0..bits {
-                        write!(file, " a{}", a).unwrap();
-                    }
-                } if num == 1 {
-                    for b in 0..bits {
-                        write!(file, " b{}", b).unwrap();
-                    }
-                } if num == 2 {
-                    for c in 0..bits {
-                        write!(file, " c{}", c).unwrap();
-                    }
-                }
-            }
-    file.write_all(b")\n").unwrap();
-
-    file.write_all(b"(").unwrap();
-        file.write_all(b"out").unwrap();
-        for num in 0..quantity {
-            if num == 1 {
-                write!(file, " resB_1").unwrap();
-            } if num == 2 {
-                write!(file, " resC_1").unwrap();
-            }
+use std::{
+    fmt,
+    str::FromStr,
+};
+use crate::{
+    code::{
+        builder::{CircuitBuilder, Var, Lc},
+        logic::{not, xnor, select, and_all, pack},
+    },
+    common::Common,
+    transform::into_field,
+};
+
+// Less proves a private a is below a public b, Range that a private b lies strictly between a public a and c.
+// the others relate a private a to a public b as their names say; Min and Max give the smaller or larger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    Min,
+    Max,
+    Range,
+}
+
+impl Kind {
+    // the name of the output variable.
+    pub fn output(&self) -> &'static str {
+        match self {
+            Kind::Less => "less",
+            Kind::LessEqual => "less_equal",
+            Kind::Greater => "greater",
+            Kind::GreaterEqual => "greater_equal",
+            Kind::Equal => "equal",
+            Kind::NotEqual => "not_equal",
+            Kind::Min => "min",
+            Kind::Max => "max",
+            Kind::Range => "between",
         }
-    file.write_all(b")\n").unwrap();
+    }
+}
 
+impl FromStr for Kind {
+    type Err = String;
 
-    file.write_all(b"(").unwrap();
-        file.write_all(b"verify").unwrap();
-        for num in 0..quantity {
-            if num == 1 {
-                write!(file, " resB_1").unwrap();
-                for b in 0..bits {
-                    write!(file, " b{}", b).unwrap();
-                }
-            } if num == 2 {
-                write!(file, " resC_1").unwrap();
-                for c in 0..bits {
-                    write!(file, " c{}", c).unwrap();
-                }
-            }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "comp" | "less" | "lt" => Ok(Kind::Less),
+            "le" => Ok(Kind::LessEqual),
+            "gt" => Ok(Kind::Greater),
+            "ge" => Ok(Kind::GreaterEqual),
+            "eq" => Ok(Kind::Equal),
+            "ne" => Ok(Kind::NotEqual),
+            "min" => Ok(Kind::Min),
+            "max" => Ok(Kind::Max),
+            "range" => Ok(Kind::Range),
+            _ => Err(format!("{} is not a comparator, try comp, le, gt, ge, eq, ne, min, max or range", s)),
         }
-    file.write_all(b")\n").unwrap();
-
-    file.write_all(b"(program\n").unwrap();
-   
-    for num in 0..quantity {
-        if num == 1 {
-            for val in 0..bits {
-                write!(
-                    file, 
-                    "   (= diffB_{} (* (+ a{} (* 250 b{})) (+ a{} (* 250 b{}))))\n", 
-                    val, val, val, val, val
-                ).unwrap();
-            }
-        } if num == 2 {
-            for val in 0..bits {
-                write!(
-                    file, 
-                    "   (= diffC_{} (* (+ a{} (* 250 c{})) (+ a{} (* 250 c{}))))\n", 
-                    val, val, val, val, val
-                ).unwrap();
-            }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Kind::Less => write!(f, "comp"),
+            Kind::LessEqual => write!(f, "le"),
+            Kind::Greater => write!(f, "gt"),
+            Kind::GreaterEqual => write!(f, "ge"),
+            Kind::Equal => write!(f, "eq"),
+            Kind::NotEqual => write!(f, "ne"),
+            Kind::Min => write!(f, "min"),
+            Kind::Max => write!(f, "max"),
+            Kind::Range => write!(f, "range"),
         }
     }
+}
 
-    for num in 0..quantity {
-        if num == 1 {
-            for val in (0..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= accB_{} (* 1 diffB_{}))\n",
-                        val, val  
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= accB_i{} (* accB_{} diffB_{})) (= accB_{} (* 1 (+ accB_{} diffB_{} (* 250 accB_i{}))))\n",
-                        val, val + 1, val, val, val + 1, val, val
-                    ).unwrap();
-                }
+// a comparator over width bit numbers, each handed over as itself and as its bits under the tag "u{width}",
+// as Knowledge::into_linked does, and checked to agree. the output, named by Kind::output(), is 1 when the
+// comparison holds, 0 when not:
+//   Range: b private, a and c public, output between = a < b < c.
+//   the rest: a private, b public, output less = a < b, less_equal = a <= b and so on.
+//   Min and Max: the output is the smaller or the larger of a and b rather than 0 or 1.
+// the Marker is handed the public numbers then the output as nums, and the public numbers again as bits.
+// signed numbers are handed over as their two's complement bits under "i{width}" and as themselves as signed
+// nums, as Knowledge::into_signed does, and checked to agree. no usize num can carry them, so the Marker is
+// handed just the output as a num, then the public bits, then the public numbers with Andromeda::with_signed.
+// Min and Max then give the two's complement of the value, as twos_complement() does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComparatorSpec {
+    pub width: usize,
+    pub kind: Kind,
+    pub signed: bool,
+}
+
+impl ComparatorSpec {
+    pub fn into(width: usize, kind: Kind) -> Result<Self, String> {
+        let spec = ComparatorSpec {
+            width: width,
+            kind: kind,
+            signed: false,
+        };
+        match into_field::width(&spec.tag()) {
+            Some(_) => Ok(spec),
+            None => Err(format!("no comparator for {} bits, try 8, 16, 32 or 64", width)),
+        }
+    }
+
+    pub fn signed(width: usize, kind: Kind) -> Result<Self, String> {
+        Self::into(width, kind).map(|spec| ComparatorSpec { signed: true, ..spec })
+    }
+
+    // the ut the Knowledge and Marker are built with.
+    pub fn tag(&self) -> String {
+        match self.signed {
+            true => format!("i{}", self.width),
+            false => format!("u{}", self.width),
+        }
+    }
+
+    pub fn builder(&self) -> CircuitBuilder {
+        let mut builder = CircuitBuilder::new();
+        match self.kind {
+            Kind::Range => {
+                let b = self.witness(&mut builder, "b");
+                let a = self.input(&mut builder, "a");
+                let c = self.input(&mut builder, "c");
+                let (above, below) = (self.less(&mut builder, &a, &b), self.less(&mut builder, &b, &c));
+                let between = builder.mul(above, below);
+                builder.output("between", between);
+            },
+            kind => {
+                let a = self.witness(&mut builder, "a");
+                let b = self.input(&mut builder, "b");
+                let out = match kind {
+                    Kind::Less => self.less(&mut builder, &a, &b),
+                    Kind::LessEqual => not(self.less(&mut builder, &b, &a)),
+                    Kind::Greater => self.less(&mut builder, &b, &a),
+                    Kind::GreaterEqual => not(self.less(&mut builder, &a, &b)),
+                    Kind::Equal => equal(&mut builder, &a, &b),
+                    Kind::NotEqual => not_equal(&mut builder, &a, &b),
+                    // the signed values are picked as their two's complement, which pack reads as unsigned.
+                    Kind::Min => {
+                        let less = self.less(&mut builder, &a, &b);
+                        select(&mut builder, less, pack(&a), pack(&b))
+                    },
+                    Kind::Max => {
+                        let less = self.less(&mut builder, &a, &b);
+                        select(&mut builder, less, pack(&b), pack(&a))
+                    },
+                    Kind::Range => unreachable!(),
+                };
+                builder.output(kind.output(), out);
+            },
+        }
+        builder
+    }
+
+    // a private number's bits, linked to the number itself.
+    fn witness(&self, builder: &mut CircuitBuilder, name: &str) -> Vec<Var> {
+        match self.signed {
+            true => builder.witness_signed(name, self.width).1,
+            false => builder.witness_number(name, self.width).1,
+        }
+    }
+
+    fn input(&self, builder: &mut CircuitBuilder, name: &str) -> Vec<Var> {
+        match self.signed {
+            true => builder.input_signed(name, self.width).1,
+            false => builder.input_number(name, self.width).1,
+        }
+    }
+
+    fn less(&self, builder: &mut CircuitBuilder, a: &[Var], b: &[Var]) -> Lc {
+        match self.signed {
+            true => signed_less(builder, a, b),
+            false => less(builder, a, b),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        self.builder().render()
+    }
+
+    pub fn reference<C, T, U, V>(&self) -> C
+    where
+        C: Common<T, U, V>,
+    {
+        self.builder().reference()
+    }
+}
+
+// 1 when the number with bits a is below the one with bits b, 0 otherwise, both least significant first.
+// either may be constant bits. walking down from the top bit, first marks the highest bit where the two differ
+// and seen counts the marks so far, so a < b exactly when b holds the 1 at the marked bit. three products a bit.
+pub fn less<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    assert_eq!(a.len(), b.len(), "comparator::less() comparing numbers of different widths");
+    let (mut seen, mut lt) = (Lc::zero(), Lc::zero());
+    let bits = a.iter().cloned().map(Into::into).zip(b.iter().cloned().map(Into::into));
+    for (a, b) in bits.rev() {
+        let (a, b): (Lc, Lc) = (a, b);
+        let ab = builder.mul(a.clone(), b.clone());
+        let differ = a + b.clone() - Lc::from(ab) * 2;
+        let first = match seen == Lc::zero() {
+            true => differ,
+            false => builder.mul(differ, Lc::constant(1u32) - seen.clone()).into(),
+        };
+        lt = lt + builder.mul(b, first.clone());
+        seen = seen + first;
+    }
+    lt
+}
+
+// 1 when a <= b, as not(b < a).
+pub fn less_equal<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    not(less(builder, b, a))
+}
+
+pub fn greater<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    less(builder, b, a)
+}
+
+pub fn greater_equal<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    not(less(builder, a, b))
+}
+
+// 1 when every bit of a matches the one of b, signed or not. unlike arith::equal it needs no hint.
+pub fn equal<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    assert_eq!(a.len(), b.len(), "comparator::equal() comparing numbers of different widths");
+    let same: Vec<Lc> = a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| xnor(builder, a.clone(), b.clone()))
+        .collect();
+    and_all(builder, same)
+}
+
+pub fn not_equal<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    not(equal(builder, a, b))
+}
+
+// the smaller of the two numbers, picked with a single product once they are compared.
+pub fn min<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    let less = less(builder, a, b);
+    select(builder, less, pack(a), pack(b))
+}
+
+pub fn max<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    let less = less(builder, a, b);
+    select(builder, less, pack(b), pack(a))
+}
+
+// less for signed numbers in two's complement. flipping the top bit shifts both by 2^(width - 1) into the
+// unsigned numbers in the same order, as -128..=127 goes to 0..=255 at 8 bits.
+pub fn signed_less<A, B>(builder: &mut CircuitBuilder, a: &[A], b: &[B]) -> Lc
+where
+    A: Into<Lc> + Clone,
+    B: Into<Lc> + Clone,
+{
+    let flip = |bits: Vec<Lc>| -> Vec<Lc> {
+        let top = bits.len().saturating_sub(1);
+        bits.into_iter()
+            .enumerate()
+            .map(|(i, b)| match i == top {
+                true => not(b),
+                false => b,
+            })
+            .collect()
+    };
+    let a = flip(a.iter().cloned().map(Into::into).collect());
+    let b = flip(b.iter().cloned().map(Into::into).collect());
+    less(builder, &a, &b)
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::{
+        CoefficientPoly,
+        groth16,
+        groth16::fr::{FrLocal, G1Local, G2Local},
+    };
+    use num::BigUint;
+    use std::str::FromStr;
+    use crate::{
+        code::{
+            builder::{CircuitBuilder, Lc, MODULUS},
+            comparator::{self, ComparatorSpec, Kind},
+        },
+        common::{CommonReference, Common},
+        crypto::Algorithm,
+        transform::into_field::twos_complement,
+        interface::{Andromeda, GoZero, MarkZero},
+        knowledge::Knowledge,
+    };
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    // proves the comparison for the private x, claiming result for it against the public numbers.
+    fn prove(spec: &ComparatorSpec, crs: &Crs, x: usize, public: Vec<usize>, result: usize) -> bool {
+        let mut out = public.clone();
+        out.push(result);
+        Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into_linked(Some(vec![x]), Some(public.clone()), spec.tag()),
+            Some(out),
+            Some(public),
+            Some(spec.tag()),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify()
+    }
+
+    #[test]
+    fn test_comparator_less() {
+        for &width in [8, 16, 32, 64].iter() {
+            let spec = ComparatorSpec::into(width, Kind::Less).unwrap();
+            let crs: Crs = spec.reference();
+            let max = match width {
+                64 => std::u64::MAX as usize,
+                _ => (1 << width) - 1,
+            };
+            for &(a, b) in [(3, 200), (4, 5), (0, 1), (max - 1, max), (1 << (width - 1), max)].iter() {
+                assert!(prove(&spec, &crs, a, vec![b], 1));
+                assert!(!prove(&spec, &crs, a, vec![b], 0));
             }
-        } if num == 2 {
-            for val in (0..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= accC_{} (* 1 diffC_{}))\n",
-                        val, val  
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= accC_i{} (* accC_{} diffC_{})) (= accC_{} (* 1 (+ accC_{} diffC_{} (* 250 accC_i{}))))\n",
-                        val, val + 1, val, val, val + 1, val, val
-                    ).unwrap();
-                }
+            for &(a, b) in [(5, 4), (200, 3), (7, 7), (0, 0), (max, max - 1), (max, 1 << (width - 1))].iter() {
+                assert!(prove(&spec, &crs, a, vec![b], 0));
+                assert!(!prove(&spec, &crs, a, vec![b], 1));
             }
         }
     }
 
-    for num in 0..quantity {
-        if num == 1 {
-            for val in (0..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= fdiffB_{} (* 1 accB_{}))\n",
-                        val, val
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= fdiffB_{} (* (+ accB_{} (* 250 accB_{})) (+ accB_{} (* 250 accB_{}))))\n",
-                        val, val + 1, val, val + 1, val
-                    ).unwrap();
-                }
+    #[test]
+    fn test_comparator_range() {
+        for &width in [8, 16, 32, 64].iter() {
+            let spec = ComparatorSpec::into(width, Kind::Range).unwrap();
+            let crs: Crs = spec.reference();
+            let top = 1 << (width - 1);
+            for &(a, b, c) in [(3, 4, 5), (0, 100, 200), (top - 1, top, top + 1)].iter() {
+                assert!(prove(&spec, &crs, b, vec![a, c], 1));
+                assert!(!prove(&spec, &crs, b, vec![a, c], 0));
             }
-        } if num == 2 {
-            for val in (0..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= fdiffC_{} (* 1 accC_{}))\n",
-                        val, val
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= fdiffC_{} (* (+ accC_{} (* 250 accC_{})) (+ accC_{} (* 250 accC_{}))))\n",
-                        val, val + 1, val, val + 1, val
-                    ).unwrap();
-                }
+            // below, at either end and above the range, and an empty range.
+            for &(a, b, c) in [(3, 2, 5), (3, 3, 5), (3, 5, 5), (3, 6, 5), (5, 4, 3), (top, top + 1, top)].iter() {
+                assert!(prove(&spec, &crs, b, vec![a, c], 0));
+                assert!(!prove(&spec, &crs, b, vec![a, c], 1));
             }
-        } 
+        }
+    }
+
+    #[test]
+    fn test_comparator_booleanity() {
+        // with b = 2 the circuit never looks at a0 once a1 differs, so a0 = 2, which makes a = 2, passes for less.
+        let spec = ComparatorSpec::into(8, Kind::Less).unwrap();
+        let crs: Crs = spec.reference();
+        let n = FrLocal::from;
+        let prove = |a0: FrLocal| {
+            let (code, qap, sg1, sg2) = crs.clone().get();
+            let mut assignments = vec![a0];
+            assignments.extend(vec![0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0].into_iter().map(n));
+            assignments.extend(vec![a0, n(2)]);
+            let weights = groth16::weights(&code, &assignments).unwrap();
+            let proof = groth16::prove(&qap, (&sg1, &sg2), &weights);
+            let inputs: Vec<FrLocal> = vec![2, 1, 0, 1, 0, 0, 0, 0, 0, 0].into_iter().map(n).collect();
+            groth16::verify::<CoefficientPoly<FrLocal>, _, _, _, _>((sg1, sg2), &inputs, proof)
+        };
+        assert!(prove(n(0)));
+        assert!(prove(n(1)));
+        assert!(!prove(n(2)));
+        assert!(!prove(FrLocal::from_str(&(MODULUS.parse::<BigUint>().unwrap() - 1u32).to_string()).unwrap()));
+    }
+
+    #[test]
+    fn test_comparator_linked() {
+        // the bits of 3 are below 4, but the number the prover hands over is 5.
+        let spec = ComparatorSpec::into(8, Kind::Less).unwrap();
+        let crs: Crs = spec.reference();
+        let prove = |bits: usize, num: usize| Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into(Some(vec![bits]), Some(vec![4]), Some(vec![num]), Some(vec![4]), Some(spec.tag())),
+            Some(vec![4, 1]),
+            Some(vec![4]),
+            Some(spec.tag()),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        assert!(prove(3, 3));
+        assert!(!prove(3, 5));
+        // nor can the public number differ from its bits.
+        let forged = Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into(Some(vec![3]), Some(vec![4]), Some(vec![3]), Some(vec![2]), Some(spec.tag())),
+            Some(vec![2, 1]),
+            Some(vec![4]),
+            Some(spec.tag()),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        assert!(!forged);
+    }
+
+    // the bits of x in two's complement at 8 bits, as the circuit takes them.
+    fn bits(x: isize) -> Vec<BigUint> {
+        let x = twos_complement(x, "i8").unwrap();
+        (0..8).map(|i| BigUint::from((x >> i) & 1)).collect()
     }
 
-    for num in 0..quantity {
-        if num == 1 {
-            for val in 0..bits {
-                write!(
-                    file,
-                    "   (= chkaB_{} (* b{} fdiffB_{}))\n",
-                    val, val, val
-                ).unwrap();
+    // x as a field element, a negative one as its additive inverse.
+    fn num(x: isize) -> BigUint {
+        match x < 0 {
+            true => MODULUS.parse::<BigUint>().unwrap() - BigUint::from(x.wrapping_neg() as usize),
+            false => BigUint::from(x as usize),
+        }
+    }
+
+    #[test]
+    fn test_comparator_signed() {
+        // every pair of 8 bit signed numbers, run through the circuit without proving, then a few proved.
+        let spec = ComparatorSpec::signed(8, Kind::Less).unwrap();
+        assert_eq!("i8", spec.tag());
+        let builder = spec.builder();
+        for a in -128..128 {
+            for b in -128..128 {
+                let out = builder.evaluate(&[bits(a), bits(b), vec![num(a), num(b)]].concat()).unwrap();
+                assert_eq!(BigUint::from((a < b) as usize), out[0], "{} < {}", a, b);
+                assert_eq!(num(b), out[9]);
             }
-        } if num == 2 {
-            for val in 0..bits {
-                write!(
-                    file,
-                    "   (= chkaC_{} (* c{} fdiffC_{}))\n",
-                    val, val, val
-                ).unwrap();
+        }
+        // the signed nums must be the values the bits hold.
+        assert!(builder.evaluate(&[bits(-3), bits(4), vec![num(253), num(4)]].concat()).is_none());
+        assert!(builder.evaluate(&[bits(-3), bits(4), vec![num(-3), num(-4)]].concat()).is_none());
+
+        let crs: Crs = spec.reference();
+        let prove = |a: isize, b: isize, signed: isize, less: usize| Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into_signed(Some(vec![a]), Some(vec![b]), spec.tag()),
+            Some(vec![less]),
+            Some(vec![twos_complement(b, "i8").unwrap()]),
+            Some(spec.tag()),
+            Algorithm::Ed25519.init_key_pair()
+        ).with_signed(vec![signed]).go().verify();
+        for &(a, b) in [(-128, 127), (127, -128), (-1, 0), (0, -1), (-1, -1), (5, -5), (-5, 5), (-128, -127)].iter() {
+            assert!(prove(a, b, b, (a < b) as usize));
+            assert!(!prove(a, b, b, (a >= b) as usize));
+            // nor does the proof hold for any other public value than the one its bits carry.
+            assert!(!prove(a, b, -b - 1, (a < b) as usize));
+        }
+        let forged = Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into(Some(vec![253]), Some(vec![4]), None, None, Some(spec.tag())).with_signed(Some(vec![-3]), Some(vec![-4])),
+            Some(vec![1]),
+            Some(vec![4]),
+            Some(spec.tag()),
+            Algorithm::Ed25519.init_key_pair()
+        ).with_signed(vec![-4]).go().verify();
+        assert!(!forged);
+    }
+
+    #[test]
+    fn test_comparator_signed_range() {
+        let spec = ComparatorSpec::signed(8, Kind::Range).unwrap();
+        let builder = spec.builder();
+        // every b against ranges across zero, wholly below it, at the ends, and empty.
+        for &(a, c) in [(-10, 10), (-128, -100), (-128, 127), (0, 1), (5, -5)].iter() {
+            for b in -128..128 {
+                let out = builder.evaluate(&[bits(b), bits(a), bits(c), vec![num(b), num(a), num(c)]].concat()).unwrap();
+                assert_eq!(BigUint::from((a < b && b < c) as usize), out[0], "{} < {} < {}", a, b, c);
             }
         }
+        let crs: Crs = spec.reference();
+        let prove = |a: isize, b: isize, c: isize, between: usize| {
+            let public = vec![twos_complement(a, "i8").unwrap(), twos_complement(c, "i8").unwrap()];
+            Andromeda::into_bits(
+                crs.clone(),
+                Knowledge::into_signed(Some(vec![b]), Some(vec![a, c]), spec.tag()),
+                Some(vec![between]),
+                Some(public),
+                Some(spec.tag()),
+                Algorithm::Ed25519.init_key_pair()
+            ).with_signed(vec![a, c]).go().verify()
+        };
+        for &(a, b, c) in [(-10, -3, 10), (-10, -10, 10), (-128, -127, -100), (-100, 50, -50)].iter() {
+            let between = (a < b && b < c) as usize;
+            assert!(prove(a, b, c, between));
+            assert!(!prove(a, b, c, 1 - between));
+        }
+    }
+
+    // what each kind but Range gives for a and b, whether they are read as signed or not.
+    fn expect<T: Ord>(kind: Kind, a: T, b: T, codes: (usize, usize)) -> usize {
+        match kind {
+            Kind::Less => (a < b) as usize,
+            Kind::LessEqual => (a <= b) as usize,
+            Kind::Greater => (a > b) as usize,
+            Kind::GreaterEqual => (a >= b) as usize,
+            Kind::Equal => (a == b) as usize,
+            Kind::NotEqual => (a != b) as usize,
+            Kind::Min => match a < b { true => codes.0, false => codes.1 },
+            Kind::Max => match a < b { true => codes.1, false => codes.0 },
+            Kind::Range => unreachable!(),
+        }
     }
-    for num in 0..quantity {
-        if num == 1 {
-            for val in (1..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= resB_{} (* 1 chkaB_{}))\n",
-                        val, val
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= resB_i{} (* resB_{} chkaB_{}))\n   (= resB_{} (* 1 (+ resB_{} chkaB_{} (* 250 resB_i{}))))\n",
-                        val, val + 1, val, val, val + 1, val, val
-                    ).unwrap();
+
+    const KINDS: [Kind; 8] = [
+        Kind::Less, Kind::LessEqual, Kind::Greater, Kind::GreaterEqual, Kind::Equal, Kind::NotEqual, Kind::Min, Kind::Max,
+    ];
+
+    #[test]
+    fn test_comparator_kinds() {
+        // every a against a spread of b at 8 bits, unsigned then signed, without proving.
+        let unsigned = |x: usize| (0..8).map(move |i| BigUint::from((x >> i) & 1));
+        for &kind in KINDS.iter() {
+            let builder = ComparatorSpec::into(8, kind).unwrap().builder();
+            for a in 0..256 {
+                for &b in [0, 1, 2, 127, 128, 129, 200, 254, 255].iter() {
+                    let inputs: Vec<BigUint> = unsigned(a).chain(unsigned(b)).chain(vec![a.into(), b.into()]).collect();
+                    let out = builder.evaluate(&inputs).unwrap();
+                    assert_eq!(BigUint::from(expect(kind, a, b, (a, b))), out[1], "{} {} {}", a, kind, b);
                 }
             }
-        } if num == 2 {
-            for val in (1..bits).rev() {
-                if val == bits - 1 {
-                    write!(
-                        file,
-                        "   (= resC_{} (* 1 chkaC_{}))\n",
-                        val, val
-                    ).unwrap();
-                } else {
-                    write!(
-                        file,
-                        "   (= resC_i{} (* resC_{} chkaC_{}))\n   (= resC_{} (* 1 (+ resC_{} chkaC_{} (* 250 resC_i{}))))\n",
-                        val, val + 1, val, val, val + 1, val, val
-                    ).unwrap();
+            let builder = ComparatorSpec::signed(8, kind).unwrap().builder();
+            for a in -128..128 {
+                for &b in [-128, -127, -1, 0, 1, 126, 127].iter() {
+                    let out = builder.evaluate(&[bits(a), bits(b), vec![num(a), num(b)]].concat()).unwrap();
+                    let codes = (twos_complement(a, "i8").unwrap(), twos_complement(b, "i8").unwrap());
+                    assert_eq!(BigUint::from(expect(kind, a, b, codes)), out[0], "{} {} {}", a, kind, b);
+                }
+            }
+        }
+
+        // and proved, at the ends of 8 and 64 bits, with each wrong claim rejected.
+        for &width in [8, 64].iter() {
+            let max = match width {
+                64 => std::u64::MAX as usize,
+                _ => (1 << width) - 1,
+            };
+            for &kind in KINDS.iter() {
+                let spec = ComparatorSpec::into(width, kind).unwrap();
+                let crs: Crs = spec.reference();
+                for &(a, b) in [(3, 200), (200, 3), (7, 7), (max, max - 1)].iter() {
+                    let out = expect(kind, a, b, (a, b));
+                    assert!(prove(&spec, &crs, a, vec![b], out));
+                    assert!(!prove(&spec, &crs, a, vec![b], out ^ 1));
                 }
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_comparator_gadgets() {
+        // the gadgets on their own, over a private a and constant bits of 5.
+        let mut builder = CircuitBuilder::new();
+        let a = builder.witness_bits("a", 4);
+        let five: Vec<Lc> = (0..4).map(|i| Lc::constant((5u32 >> i) & 1)).collect();
+        let outs = vec![
+            comparator::less_equal(&mut builder, &a, &five),
+            comparator::greater(&mut builder, &a, &five),
+            comparator::greater_equal(&mut builder, &a, &five),
+            comparator::equal(&mut builder, &a, &five),
+            comparator::not_equal(&mut builder, &a, &five),
+            comparator::min(&mut builder, &a, &five),
+            comparator::max(&mut builder, &a, &five),
+        ];
+        for (i, out) in outs.into_iter().enumerate() {
+            builder.output(&format!("out{}", i), out);
+        }
+        for a in 0..16usize {
+            let inputs: Vec<BigUint> = (0..4).map(|i| BigUint::from((a >> i) & 1)).collect();
+            let expect: Vec<BigUint> = vec![
+                (a <= 5) as usize, (a > 5) as usize, (a >= 5) as usize, (a == 5) as usize, (a != 5) as usize,
+                a.min(5), a.max(5),
+            ].into_iter().map(BigUint::from).collect();
+            assert_eq!(Some(expect), builder.evaluate(&inputs));
+        }
+    }
+
+    #[test]
+    fn test_comparator_spec() {
+        assert!(ComparatorSpec::into(7, Kind::Less).is_err());
+        assert_eq!(Ok(Kind::Less), "comp".parse());
+        assert_eq!(Ok(Kind::Range), "range".parse());
+        for &kind in KINDS.iter() {
+            assert_eq!(Ok(kind), kind.to_string().parse());
+        }
+        assert!(ComparatorSpec::into(8, Kind::GreaterEqual).unwrap().render().contains("(out greater_equal)"));
+        assert!("between".parse::<Kind>().is_err());
+        let render = ComparatorSpec::into(8, Kind::Range).unwrap().render();
+        assert!(render.starts_with("(in b0 b1 b2 b3 b4 b5 b6 b7 a0 "));
+        assert!(render.contains(" c7 b a c)\n(out between)\n(verify a c between a0 "));
+    }
+}
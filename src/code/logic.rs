@@ -0,0 +1,186 @@
+use num::{BigUint, One};
+use crate::code::builder::{CircuitBuilder, Var, Lc};
+
+// gates over bits, each taking and giving back a combination that is 0 or 1 whenever its inputs are.
+// the constants a gate needs are coefficients of the combination, so the prover never hands over a -1.
+// the bits themselves should come from witness_bits, input_bits or another gate, all of which keep them boolean.
+
+pub fn not<A: Into<Lc>>(a: A) -> Lc {
+    Lc::constant(1u32) - a.into()
+}
+
+pub fn and<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    builder.mul(a, b).into()
+}
+
+// a + b - ab.
+pub fn or<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    let (a, b) = (a.into(), b.into());
+    let ab = builder.mul(a.clone(), b.clone());
+    a + b - ab
+}
+
+// a + b - 2ab.
+pub fn xor<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    let (a, b) = (a.into(), b.into());
+    let ab = builder.mul(a.clone(), b.clone());
+    a + b - Lc::from(ab) * 2
+}
+
+pub fn nand<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    not(and(builder, a, b))
+}
+
+pub fn nor<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    not(or(builder, a, b))
+}
+
+pub fn xnor<A: Into<Lc>, B: Into<Lc>>(builder: &mut CircuitBuilder, a: A, b: B) -> Lc {
+    not(xor(builder, a, b))
+}
+
+// a when s is 1, b when s is 0, as b + s(a - b).
+pub fn select<S, A, B>(builder: &mut CircuitBuilder, s: S, a: A, b: B) -> Lc
+where
+    S: Into<Lc>,
+    A: Into<Lc>,
+    B: Into<Lc>,
+{
+    let (a, b) = (a.into(), b.into());
+    let pick = builder.mul(s, a - b.clone());
+    b + pick
+}
+
+// 1 when every bit is, one product for each bit after the first. nothing at all is 1.
+pub fn and_all<I, L>(builder: &mut CircuitBuilder, bits: I) -> Lc
+where
+    I: IntoIterator<Item = L>,
+    L: Into<Lc>,
+{
+    let mut bits = bits.into_iter().map(Into::into);
+    match bits.next() {
+        Some(first) => bits.fold(first, |acc, b| and(builder, acc, b)),
+        None => Lc::constant(1u32),
+    }
+}
+
+// 1 when any bit is, as not(and_all(not(bits))). nothing at all is 0.
+pub fn or_all<I, L>(builder: &mut CircuitBuilder, bits: I) -> Lc
+where
+    I: IntoIterator<Item = L>,
+    L: Into<Lc>,
+{
+    let nots: Vec<Lc> = bits.into_iter().map(not).collect();
+    not(and_all(builder, nots))
+}
+
+// gate applied to each pair of bits in turn, for vectors of the same width.
+pub fn bitwise<F>(builder: &mut CircuitBuilder, a: &[Var], b: &[Var], gate: F) -> Vec<Lc>
+where
+    F: Fn(&mut CircuitBuilder, Lc, Lc) -> Lc,
+{
+    assert_eq!(a.len(), b.len(), "logic::bitwise() combining vectors of different widths");
+    a.iter().zip(b.iter()).map(|(&a, &b)| gate(builder, a.into(), b.into())).collect()
+}
+
+// the number whose bits, least significant first, these are.
+pub fn pack<L: Into<Lc> + Clone>(bits: &[L]) -> Lc {
+    bits.iter()
+        .enumerate()
+        .fold(Lc::zero(), |sum, (i, b)| sum + b.clone().into().scale(BigUint::one() << i))
+}
+
+// the signed number whose two's complement bits, least significant first, these are: the top bit counts -2^(width - 1).
+pub fn pack_signed<L: Into<Lc> + Clone>(bits: &[L]) -> Lc {
+    match bits.split_last() {
+        Some((top, rest)) => pack(rest) - top.clone().into().scale(BigUint::one() << rest.len()),
+        None => Lc::zero(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::groth16::fr::{FrLocal, G1Local, G2Local};
+    use num::BigUint;
+    use crate::{
+        code::{
+            builder::{CircuitBuilder, Lc},
+            logic::{not, and, or, xor, nand, nor, xnor, select, and_all, or_all, bitwise, pack, pack_signed},
+        },
+        transform::into_field::{IntoField, twos_complement},
+        common::CommonReference,
+        crypto::Algorithm,
+        interface::{Andromeda, GoZero, MarkZero},
+        knowledge::Knowledge,
+    };
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    #[test]
+    fn test_logic_gates() {
+        // every gate across the bytes a and c, private, and b, public, each output packed back into a byte.
+        let mut builder = CircuitBuilder::new();
+        let a = builder.witness_bits("a", 8);
+        let c = builder.witness_bits("c", 8);
+        let b = builder.input_bits("b", 8);
+        let gates: Vec<(&str, fn(&mut CircuitBuilder, Lc, Lc) -> Lc)> = vec![
+            ("and", and), ("or", or), ("xor", xor), ("nand", nand), ("nor", nor), ("xnor", xnor),
+        ];
+        let nots: Vec<_> = a.iter().map(|&a| not(a)).collect();
+        builder.output("not", pack(&nots));
+        for (name, gate) in gates {
+            let bits = bitwise(&mut builder, &a, &b, gate);
+            builder.output(name, pack(&bits));
+        }
+        let muxed: Vec<_> = (0..8).map(|i| select(&mut builder, a[i], b[i], c[i])).collect();
+        builder.output("mux", pack(&muxed));
+        let all = and_all(&mut builder, a.clone());
+        builder.output("all", all);
+        let any = or_all(&mut builder, a.clone());
+        builder.output("any", any);
+        let crs: Crs = builder.reference();
+
+        let expect = |a: usize, b: usize, c: usize| vec![
+            !a & 0xff, a & b, a | b, a ^ b, !(a & b) & 0xff, !(a | b) & 0xff, !(a ^ b) & 0xff,
+            (a & b) | (!a & c), (a == 0xff) as usize, (a != 0) as usize,
+        ];
+        let prove = |a: usize, b: usize, c: usize, out: Vec<usize>| Andromeda::into_bits(
+            crs.clone(),
+            Knowledge::into(Some(vec![a, c]), Some(vec![b]), None, None, Some(String::from("u8"))),
+            Some(out),
+            Some(vec![b]),
+            Some(String::from("u8")),
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        for &(a, b, c) in [(0, 0, 0), (0xff, 0, 0xaa), (0b1100_1010, 0b1010_0110, 0b0101_1100), (0xff, 0xff, 0xff), (1, 0x80, 0x7f)].iter() {
+            assert!(prove(a, b, c, expect(a, b, c)));
+            for i in 0..10 {
+                let mut wrong = expect(a, b, c);
+                wrong[i] ^= 1;
+                assert!(!prove(a, b, c, wrong));
+            }
+        }
+    }
+
+    #[test]
+    fn test_pack_signed() {
+        // every 8 bit signed number, offset by 128 to come out as a usize.
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness_bits("x", 8);
+        builder.output("y", pack_signed(&x) + 128);
+        for x in -128..128isize {
+            let bits = vec![twos_complement(x, "i8").unwrap()].collect_bits::<FrLocal>(&String::from("i8"));
+            let bits: Vec<BigUint> = bits.iter().map(|b| BigUint::from((*b == FrLocal::from(1)) as u32)).collect();
+            assert_eq!(Some(vec![BigUint::from((x + 128) as usize)]), builder.evaluate(&bits));
+        }
+    }
+
+    #[test]
+    fn test_logic_empty() {
+        let mut builder = CircuitBuilder::new();
+        let none: Vec<Lc> = Vec::new();
+        assert_eq!(Some(1u32.into()), and_all(&mut builder, none.clone()).as_constant());
+        assert_eq!(Some(0u32.into()), or_all(&mut builder, none).as_constant());
+        assert_eq!(0, builder.inputs_len());
+    }
+}
@@ -0,0 +1,294 @@
+use num::{BigUint, Zero};
+use ring::{
+    digest,
+    rand::{SecureRandom, SystemRandom},
+};
+use std::str::FromStr;
+use zksnark::field::Field;
+use crate::{
+    code::builder::{CircuitBuilder, Lc, MODULUS},
+    common::Common,
+};
+
+// MiMC-7 over the scalar field of FrLocal: each of the ROUNDS rounds takes x to (x + k + c)^7, and the
+// cipher ends by adding the key once more. 7 is coprime to r - 1, so each round permutes the field, and
+// 91 rounds, the ceiling of log_7 r, leave no low degree interpolation. hash chains the cipher as
+// Miyaguchi-Preneel, h = h + x + E_h(x) for each x in turn from the key, so a digest is four products
+// a round for every input. the native functions below give the same values outside the circuit,
+// for the prover to work out a digest or for checking one against a proof.
+
+pub const ROUNDS: usize = 91;
+
+// the round constants, the first 0 and each other the SHA-256 of "zero_orb mimc7" and the round as
+// 8 big endian bytes, read big endian and reduced into the field.
+pub fn constants() -> Vec<BigUint> {
+    let r = MODULUS.parse::<BigUint>().expect("mimc::constants() parsing the scalar field modulus");
+    (0..ROUNDS)
+        .map(|i| match i {
+            0 => BigUint::zero(),
+            _ => {
+                let mut seed = b"zero_orb mimc7".to_vec();
+                seed.extend_from_slice(&(i as u64).to_be_bytes());
+                BigUint::from_bytes_be(digest::digest(&digest::SHA256, &seed).as_ref()) % &r
+            },
+        })
+        .collect()
+}
+
+// E_k(x) in the circuit.
+pub fn encrypt<X, K>(builder: &mut CircuitBuilder, x: X, k: K) -> Lc
+where
+    X: Into<Lc>,
+    K: Into<Lc>,
+{
+    let (mut x, k) = (x.into(), k.into());
+    for c in constants() {
+        let t = x + k.clone() + c;
+        let t2 = builder.mul(t.clone(), t.clone());
+        let t4 = builder.mul(t2, t2);
+        let t6 = builder.mul(t4, t2);
+        x = builder.mul(t6, t).into();
+    }
+    x + k
+}
+
+// the digest of the inputs under the key, 0 for a plain hash.
+pub fn hash<I, L, K>(builder: &mut CircuitBuilder, inputs: I, key: K) -> Lc
+where
+    I: IntoIterator<Item = L>,
+    L: Into<Lc>,
+    K: Into<Lc>,
+{
+    inputs.into_iter().map(Into::into).fold(key.into(), |h, x: Lc| {
+        let e = encrypt(builder, x.clone(), h.clone());
+        h + x + e
+    })
+}
+
+fn element<T: FromStr>(c: &BigUint) -> T {
+    match T::from_str(&c.to_string()) {
+        Ok(c) => c,
+        Err(_) => panic!("mimc::element() {} is not a field element", c),
+    }
+}
+
+// E_k(x) outside the circuit.
+pub fn encrypt_native<T: Field + FromStr>(x: T, k: T) -> T {
+    let x = constants().iter().fold(x, |x, c| {
+        let t = x + k + element(c);
+        let t2 = t * t;
+        let t4 = t2 * t2;
+        t4 * t2 * t
+    });
+    x + k
+}
+
+pub fn hash_native<T: Field + FromStr>(inputs: &[T], key: T) -> T {
+    inputs.iter().fold(key, |h, &x| h + x + encrypt_native(x, h))
+}
+
+// a key for a blinded hash: 64 random bytes reduced into the field, so it is as good as uniform.
+pub fn salt() -> BigUint {
+    let mut bytes = [0u8; 64];
+    SystemRandom::new().fill(&mut bytes).expect("mimc::salt() filling the salt from SystemRandom");
+    BigUint::from_bytes_be(&bytes) % MODULUS.parse::<BigUint>().expect("mimc::salt() parsing the scalar field modulus")
+}
+
+// a program proving the digest of inputs private nums as its one output "hash".
+// the plain hash is keyed with 0 and hides nothing a verifier can guess: a low entropy input, an age
+// or a yes or no, is found by hashing each candidate and comparing. a blinded one is keyed with a private
+// salt, handed over as the one hint after the nums, which makes the digest a commitment that only the
+// salt opens. the digest is a field element no usize can carry, so it is an output element, handed over
+// with Andromeda::with_elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashSpec {
+    pub inputs: usize,
+    pub blinded: bool,
+}
+
+impl HashSpec {
+    pub fn into(inputs: usize) -> Result<Self, String> {
+        match inputs {
+            0 => Err(String::from("a hash needs at least one input")),
+            _ => Ok(HashSpec {
+                inputs: inputs,
+                blinded: false,
+            }),
+        }
+    }
+
+    // as into, keyed with a salt from mimc::salt.
+    pub fn blinded(inputs: usize) -> Result<Self, String> {
+        Self::into(inputs).map(|spec| HashSpec { blinded: true, ..spec })
+    }
+
+    pub fn builder(&self) -> CircuitBuilder {
+        let mut builder = CircuitBuilder::new();
+        let inputs: Vec<_> = (0..self.inputs).map(|i| builder.witness(&format!("x{}", i))).collect();
+        let key: Lc = match self.blinded {
+            true => builder.hint().into(),
+            false => Lc::from(0),
+        };
+        let digest = hash(&mut builder, inputs, key);
+        builder.output_element("hash", digest);
+        builder
+    }
+
+    pub fn render(&self) -> String {
+        self.builder().render()
+    }
+
+    pub fn reference<C, T, U, V>(&self) -> C
+    where
+        C: Common<T, U, V>,
+    {
+        self.builder().reference()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::{
+        CoefficientPoly,
+        groth16,
+        groth16::fr::{FrLocal, G1Local, G2Local},
+    };
+    use num::BigUint;
+    use std::str::FromStr;
+    use crate::{
+        code::{
+            builder::{CircuitBuilder, MODULUS},
+            mimc::{self, HashSpec, ROUNDS},
+        },
+        common::{CommonReference, Common},
+        crypto::Algorithm,
+        interface::{Andromeda, GoZero, MarkZero, ZeroRef, FromZeroRef, Columns, PartsError, BackPack},
+        knowledge::Knowledge,
+    };
+    use zksnark::groth16::fr::GtLocal;
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    fn field(x: &BigUint) -> FrLocal {
+        FrLocal::from_str(&x.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_mimc_native() {
+        // the circuit, run without proving, agrees with the native cipher and hash, keys and inputs at either end
+        // of the field included.
+        let minus_one = MODULUS.parse::<BigUint>().unwrap() - 1u32;
+        let values = vec![BigUint::from(0u32), BigUint::from(1u32), BigUint::from(123_456_789u32), minus_one];
+
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness("x");
+        let k = builder.witness("k");
+        let e = mimc::encrypt(&mut builder, x, k);
+        builder.output("e", e);
+        assert_eq!(4 * ROUNDS + 1, builder.render().matches("(= ").count());
+        for x in values.iter() {
+            for k in values.iter() {
+                let out = builder.evaluate(&[x.clone(), k.clone()]).unwrap();
+                assert!(field(&out[0]) == mimc::encrypt_native(field(x), field(k)));
+            }
+        }
+
+        let spec = HashSpec::into(3).unwrap();
+        let builder = spec.builder();
+        for x in values.iter() {
+            let inputs = vec![x.clone(), BigUint::from(7u32), x.clone()];
+            let out = builder.evaluate(&inputs).unwrap();
+            let native: Vec<FrLocal> = inputs.iter().map(field).collect();
+            assert!(field(&out[0]) == mimc::hash_native(&native, FrLocal::from(0)));
+        }
+
+        // different inputs, or the same in another order, give different digests.
+        let n = FrLocal::from;
+        let digest = mimc::hash_native(&[n(1), n(2)], n(0));
+        assert!(digest != mimc::hash_native(&[n(2), n(1)], n(0)));
+        assert!(digest != mimc::hash_native(&[n(1), n(3)], n(0)));
+        assert!(digest != mimc::hash_native(&[n(1), n(2)], n(1)));
+        assert!(digest != mimc::hash_native(&[n(1)], n(0)));
+        assert_eq!(ROUNDS, mimc::constants().len());
+        assert!(HashSpec::into(0).is_err());
+    }
+
+    #[test]
+    fn test_mimc_proof() {
+        // a proof of the native digest of a private input verifies, and of any other digest does not.
+        let crs: Crs = HashSpec::into(1).unwrap().reference();
+        let n = FrLocal::from;
+        let prove = |x: FrLocal, digest: FrLocal| {
+            let (code, qap, sg1, sg2) = crs.clone().get();
+            let weights = groth16::weights(&code, &[x]).unwrap();
+            let proof = groth16::prove(&qap, (&sg1, &sg2), &weights);
+            groth16::verify::<CoefficientPoly<FrLocal>, _, _, _, _>((sg1, sg2), &[digest], proof)
+        };
+        let digest = mimc::hash_native(&[n(42)], n(0));
+        assert!(prove(n(42), digest));
+        assert!(!prove(n(43), digest));
+        assert!(!prove(n(42), digest + n(1)));
+    }
+
+    #[test]
+    fn test_mimc_orb() {
+        // the digest travels in the orb as a field element, and the orb verifies only for the digest of its inputs.
+        type Orb = BackPack<Crs, FrLocal, G1Local, G2Local, GtLocal>;
+        let spec = HashSpec::into(2).unwrap();
+        let crs: Crs = spec.reference();
+        let n = |x: usize| BigUint::from(x);
+        let digest = spec.builder().evaluate(&[n(20), n(5)]).unwrap().remove(0);
+        assert!(field(&digest) == mimc::hash_native(&[FrLocal::from(20), FrLocal::from(5)], FrLocal::from(0)));
+        let orb = |digest: &BigUint| Andromeda::into(
+            crs.clone(),
+            Knowledge::into_num_only(Some(vec![20, 5]), None),
+            None,
+            Algorithm::Ed25519.init_key_pair()
+        ).with_elements(vec![digest.clone()]).go();
+        assert!(orb(&digest).verify());
+        assert!(!orb(&(digest.clone() + 1u32)).verify());
+        let minus_one = MODULUS.parse::<BigUint>().unwrap() - 1u32;
+        assert!(!orb(&minus_one).verify());
+
+        // the digest survives the database columns, which turn away one that is not a field element.
+        let good = orb(&digest);
+        assert_eq!(good.vf, Orb::from_copy(good.copy_str()).unwrap().vf);
+        assert!(Orb::from_copy(good.copy_str()).unwrap().verify());
+        match Orb::from_parts(Columns { vf: "[\"0x1f\"]".as_bytes(), ..good.copy_str().as_bytes() }) {
+            Err(PartsError::Invalid("vf", _)) => {},
+            _ => panic!("from_parts accepted a digest that is not a field element"),
+        }
+        // nor does an orb verify with the digest left out.
+        let mut bare = orb(&digest);
+        bare.vf = None;
+        assert!(!bare.verify());
+    }
+
+    #[test]
+    fn test_mimc_blinded() {
+        // a blinded digest is keyed with the salt, so guessing the input and hashing it gives nothing away,
+        // and the orb verifies only with the salt it was committed under.
+        type Orb = BackPack<Crs, FrLocal, G1Local, G2Local, GtLocal>;
+        let spec = HashSpec::blinded(1).unwrap();
+        assert_eq!(HashSpec { inputs: 1, blinded: true }, spec);
+        assert!(HashSpec::blinded(0).is_err());
+        let crs: Crs = spec.reference();
+        let (salt, other) = (mimc::salt(), mimc::salt());
+        assert!(salt != other);
+        let digest = spec.builder().evaluate(&[BigUint::from(1u32), salt.clone()]).unwrap().remove(0);
+        assert!(field(&digest) == mimc::hash_native(&[FrLocal::from(1)], field(&salt)));
+        assert!(field(&digest) != mimc::hash_native(&[FrLocal::from(1)], FrLocal::from(0)));
+        assert!(digest != spec.builder().evaluate(&[BigUint::from(1u32), other.clone()]).unwrap().remove(0));
+
+        let orb = |salt: &BigUint| -> Orb {
+            Andromeda::into(
+                crs.clone(),
+                Knowledge::into_num_only(Some(vec![1]), None).with_hints(vec![salt.clone()]),
+                None,
+                Algorithm::Ed25519.init_key_pair()
+            ).with_elements(vec![digest.clone()]).go()
+        };
+        assert!(orb(&salt).verify());
+        assert!(!orb(&other).verify());
+    }
+}
@@ -1,3 +1,12 @@
+pub mod comparator;
+pub mod builder;
+pub mod logic;
+pub mod arith;
+pub mod range;
+pub mod mimc;
+
+pub use self::builder::{CircuitBuilder, Var, Lc};
+
 #[test]
 fn test_AND_gate() {
     use crate::knowledge::{Knowledge, Marker, zkProof, zkVerify};
@@ -27,6 +36,15 @@ fn test_AND_gate() {
             EdDSA::<String>::init_key_pair()
         ).go().verify()
     );
+    // a 2 where a bit belongs would give 2 AND 1 = 2, were the inputs not constrained to bits.
+    assert!(
+        !Andromeda::into(
+            crs.clone(),
+            Knowledge::into(None, None, Some(vec![2, 1, 1, 1, 1, 1, 0, 1]), None, None),
+            Some(vec![2, 1, 0, 1]),
+            EdDSA::<String>::init_key_pair()
+        ).go().verify()
+    );
 }
 
 #[test]
@@ -37,7 +55,7 @@ fn test_OR_gate() {
     use crate::interface::{Andromeda, GoZero, MarkZero};
     use zksnark::groth16::fr::{FrLocal, G1Local, G2Local};
 
-    // an OR gate is analytically represented as f(a, b) = a + b - ab.
+    // an OR gate is analytically represented as f(a, b) = a + b - ab, the -1 a coefficient rather than a witness.
 
     let crs: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::new(
         std::fs::read_to_string("src/code/or.zk").expect("internal_test: reading CommonReference from string")
@@ -45,11 +63,11 @@ fn test_OR_gate() {
     let weights = Knowledge::into(
         None, 
         None,
-        Some(vec![0, 1, 0b1001]),
+        Some(vec![0, 1]),
         None,
         None,
     );
-    // we are expecting that running 0111 and 0101 through an AND gate will get us 0101 back.
+    // 0 OR 1 is 1.
     assert_eq!(
         true,
         Andromeda::into(
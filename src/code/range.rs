@@ -0,0 +1,174 @@
+use num::{BigUint, One};
+use crate::code::{
+    builder::{CircuitBuilder, Var, Lc, MODULUS},
+    arith::enforce_equal,
+    logic::pack,
+};
+
+// one end of a range, fixed in the circuit, or a public num the verifier is handed with the others.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bound {
+    Constant(u64),
+    Public(Var),
+}
+
+impl Bound {
+    fn lc(&self) -> Lc {
+        match self {
+            Bound::Constant(c) => Lc::constant(*c),
+            Bound::Public(v) => Lc::from(*v),
+        }
+    }
+}
+
+// lo <= x <= hi, both ends included. x - lo and hi - x are hinted as bits() bits each, which puts both below
+// 2^bits(); their sum is hi - lo, so neither can have wrapped around the field and x lies between the two.
+// with both ends constant the bits are as few as hi - lo needs, 7 for 18 to 120. with a public end they are
+// width, which should hold hi - lo for every bound the verifier may hand over, or an honest x cannot be proved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub lo: Bound,
+    pub hi: Bound,
+    pub width: usize,
+}
+
+impl Range {
+    pub fn into(lo: Bound, hi: Bound, width: usize) -> Self {
+        assert!(width <= 64, "Range::into() {} bits are too many, at most 64 are allowed", width);
+        Range {
+            lo: lo,
+            hi: hi,
+            width: width,
+        }
+    }
+
+    pub fn constant(lo: u64, hi: u64) -> Self {
+        assert!(lo <= hi, "Range::constant() {} to {} is empty", lo, hi);
+        Self::into(Bound::Constant(lo), Bound::Constant(hi), 64)
+    }
+
+    pub fn public(lo: Var, hi: Var, width: usize) -> Self {
+        Self::into(Bound::Public(lo), Bound::Public(hi), width)
+    }
+
+    // the bits each of x - lo and hi - x is hinted as.
+    pub fn bits(&self) -> usize {
+        match (self.lo, self.hi) {
+            (Bound::Constant(lo), Bound::Constant(hi)) => 64 - (hi - lo).leading_zeros() as usize,
+            (_, _) => self.width,
+        }
+    }
+
+    // constrains x to the range. the proof cannot be made for an x outside it.
+    pub fn enforce<X: Into<Lc>>(&self, builder: &mut CircuitBuilder, x: X) {
+        let x = x.into();
+        let above = builder.hint_bits(self.bits());
+        let below = builder.hint_bits(self.bits());
+        enforce_equal(builder, pack(&above), x.clone() - self.lo.lc());
+        enforce_equal(builder, pack(&below), self.hi.lc() - x);
+    }
+
+    // the hints for x between lo and hi, the public ends' values taken from lo and hi.
+    // for an x outside the range they are the low bits of a field element, which the circuit rejects.
+    pub fn hint(&self, x: u64, lo: u64, hi: u64) -> Vec<BigUint> {
+        let (lo, hi) = (self.end(self.lo, lo), self.end(self.hi, hi));
+        let r = MODULUS.parse::<BigUint>().expect("Range::hint() parsing the scalar field modulus");
+        let diff = |a: u64, b: u64| (BigUint::from(a) + &r - BigUint::from(b)) % &r;
+        let bits = |d: BigUint| (0..self.bits()).map(move |i| (&d >> i) & BigUint::one());
+        bits(diff(x, lo)).chain(bits(diff(hi, x))).collect()
+    }
+
+    fn end(&self, bound: Bound, given: u64) -> u64 {
+        match bound {
+            Bound::Constant(c) if c != given => panic!("Range::hint() the bound is fixed at {}, not {}", c, given),
+            Bound::Constant(c) => c,
+            Bound::Public(_) => given,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::groth16::fr::{FrLocal, G1Local, G2Local};
+    use num::BigUint;
+    use crate::{
+        code::{
+            builder::CircuitBuilder,
+            range::{Range, Bound},
+        },
+        common::CommonReference,
+        crypto::Algorithm,
+        interface::{Andromeda, GoZero, MarkZero},
+        knowledge::Knowledge,
+    };
+
+    type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+
+    #[test]
+    fn test_range_constant() {
+        // 18 <= age <= 120.
+        let mut builder = CircuitBuilder::new();
+        let age = builder.witness("age");
+        let range = Range::constant(18, 120);
+        range.enforce(&mut builder, age);
+        assert_eq!(7, range.bits());
+        assert_eq!(14, builder.inputs_len() - 1);
+        let crs: Crs = builder.reference();
+        let prove = |age: u64, hints: Vec<BigUint>| Andromeda::into(
+            crs.clone(),
+            Knowledge::into_num_only(Some(vec![age as usize]), None).with_hints(hints),
+            None,
+            Algorithm::Ed25519.init_key_pair()
+        ).go().verify();
+        for &age in [18, 19, 50, 119, 120].iter() {
+            assert!(prove(age, range.hint(age, 18, 120)));
+        }
+        for &age in [0, 17, 121, 128, 200].iter() {
+            assert!(!prove(age, range.hint(age, 18, 120)));
+        }
+        // nor do the hints of an age in range carry another.
+        assert!(!prove(17, range.hint(18, 18, 120)));
+        assert!(!prove(121, range.hint(120, 18, 120)));
+
+        assert_eq!(0, Range::constant(5, 5).bits());
+        assert_eq!(1, Range::constant(5, 6).bits());
+        assert_eq!(64, Range::constant(0, std::u64::MAX).bits());
+    }
+
+    #[test]
+    fn test_range_public() {
+        // the verifier hands over the bounds, a constant floor of 18 under a public ceiling.
+        let mut builder = CircuitBuilder::new();
+        let x = builder.witness("x");
+        let lo = builder.input("lo");
+        let hi = builder.input("hi");
+        let range = Range::public(lo, hi, 16);
+        range.enforce(&mut builder, x);
+        let ceiling = builder.input("ceiling");
+        let capped = Range::into(Bound::Constant(18), Bound::Public(ceiling), 16);
+        capped.enforce(&mut builder, x);
+        let crs: Crs = builder.reference();
+        let prove = |x: u64, lo: u64, hi: u64, ceiling: u64, verifier: Vec<usize>| {
+            let mut hints = range.hint(x, lo, hi);
+            hints.extend(capped.hint(x, 18, ceiling));
+            Andromeda::into(
+                crs.clone(),
+                Knowledge::into_num_only(Some(vec![x as usize]), Some(vec![lo as usize, hi as usize, ceiling as usize]))
+                    .with_hints(hints),
+                Some(verifier),
+                Algorithm::Ed25519.init_key_pair()
+            ).go().verify()
+        };
+        assert!(prove(20, 3, 900, 65, vec![3, 900, 65]));
+        assert!(prove(20, 20, 20, 20, vec![20, 20, 20]));
+        assert!(prove(65, 0, 65535, 65, vec![0, 65535, 65]));
+        assert!(!prove(2, 3, 900, 65, vec![3, 900, 65]));
+        assert!(!prove(901, 3, 900, 1000, vec![3, 900, 1000]));
+        assert!(!prove(66, 3, 900, 65, vec![3, 900, 65]));
+        assert!(!prove(17, 3, 900, 65, vec![3, 900, 65]));
+        // an empty range holds nothing.
+        assert!(!prove(20, 900, 3, 65, vec![900, 3, 65]));
+        // and the proof is only good for the bounds it was made with.
+        assert!(!prove(20, 3, 900, 65, vec![3, 19, 65]));
+    }
+}
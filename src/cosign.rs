@@ -0,0 +1,158 @@
+use serde_derive::{Serialize, Deserialize};
+use std::collections::HashSet;
+use crate::crypto::Algorithm;
+
+// a party whose approval an orb can require, named by its scheme and public key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Signer {
+    pub alg: Algorithm,
+    pub puk: Box<[u8]>,
+}
+
+impl Signer {
+    pub fn into(alg: Algorithm, puk: Box<[u8]>) -> Self {
+        Signer {
+            alg: alg,
+            puk: puk,
+        }
+    }
+
+    // the signer holding this key pair, if it is one zero_orb can sign with.
+    pub fn of_key(key_pair: &Box<[u8]>) -> Option<Self> {
+        let alg = Algorithm::of_key(key_pair)?;
+        Some(Signer::into(alg, alg.public_key(key_pair)))
+    }
+}
+
+// m of n: at least threshold of the signers must co-sign before the orb is valid.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Policy {
+    pub threshold: usize,
+    pub signers: Vec<Signer>,
+}
+
+impl Policy {
+    pub fn into(threshold: usize, signers: Vec<Signer>) -> Result<Self, String> {
+        let policy = Policy {
+            threshold: threshold,
+            signers: signers,
+        };
+        policy.check()?;
+        Ok(policy)
+    }
+
+    // the threshold must be reachable and every signer listed once, so one key cannot count twice.
+    pub fn check(&self) -> Result<(), String> {
+        if self.threshold == 0 || self.threshold > self.signers.len() {
+            return Err(format!("a threshold of {} cannot be met by {} signers", self.threshold, self.signers.len()))
+        }
+        let mut seen = HashSet::new();
+        for signer in self.signers.iter() {
+            if signer.puk.len() != signer.alg.public_key_len() {
+                return Err(format!("expected a {} byte public key for {}, found {}", signer.alg.public_key_len(), signer.alg, signer.puk.len()))
+            }
+            if !seen.insert(&signer.puk) {
+                return Err(String::from("a signer is listed more than once"))
+            }
+        }
+        Ok(())
+    }
+
+    pub fn position(&self, signer: &Signer) -> Option<usize> {
+        self.signers.iter().position(|s| s == signer)
+    }
+}
+
+// the policy is fixed when the orb is proved and covered by the prover's signature.
+// the co-signatures are gathered afterwards, each over the same message, keyed by the signer's place in the policy.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoSign {
+    pub policy: Policy,
+    pub sigs: Vec<(usize, Box<[u8]>)>,
+}
+
+impl CoSign {
+    pub fn into(policy: Policy) -> Self {
+        CoSign {
+            policy: policy,
+            sigs: Vec::new(),
+        }
+    }
+
+    // a signature is only kept once it verifies, replacing any earlier one from the same signer.
+    pub fn add<M: AsRef<[u8]>>(&mut self, msg: M, signer: usize, sig: Box<[u8]>) -> Result<(), String> {
+        let expected = match self.policy.signers.get(signer) {
+            Some(expected) => expected,
+            None => return Err(format!("the policy has no signer {}", signer)),
+        };
+        if !expected.alg.verify_signature(msg, &sig, &expected.puk) {
+            return Err(format!("the co-signature does not match signer {}", signer))
+        }
+        self.sigs.retain(|(i, _)| *i != signer);
+        self.sigs.push((signer, sig));
+        Ok(())
+    }
+
+    // the number of distinct signers with a valid co-signature over msg.
+    pub fn approvals<M: AsRef<[u8]>>(&self, msg: M) -> usize {
+        let mut approved = HashSet::new();
+        for (i, sig) in self.sigs.iter() {
+            if let Some(signer) = self.policy.signers.get(*i) {
+                if signer.alg.verify_signature(msg.as_ref(), sig, &signer.puk) {
+                    approved.insert(*i);
+                }
+            }
+        }
+        approved.len()
+    }
+
+    pub fn check<M: AsRef<[u8]>>(&self, msg: M) -> Result<(), String> {
+        self.policy.check()?;
+        match self.approvals(msg) {
+            n if n >= self.policy.threshold => Ok(()),
+            n => Err(format!("{} of the {} required co-signatures verify", n, self.policy.threshold)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cosign::{Signer, Policy, CoSign},
+        crypto::Algorithm,
+    };
+
+    #[test]
+    fn test_cosign() {
+        let keys = vec![Algorithm::Ed25519, Algorithm::EcdsaP256, Algorithm::EcdsaP384]
+            .into_iter()
+            .map(|alg| alg.init_key_pair())
+            .collect::<Vec<_>>();
+        let signers = keys.iter().map(|kp| Signer::of_key(kp).unwrap()).collect::<Vec<_>>();
+        assert!(Policy::into(0, signers.clone()).is_err());
+        assert!(Policy::into(4, signers.clone()).is_err());
+        assert!(Policy::into(1, vec![signers[0].clone(), signers[0].clone()]).is_err());
+
+        let policy = Policy::into(2, signers.clone()).unwrap();
+        assert_eq!(Some(2), policy.position(&signers[2]));
+        let mut cosign = CoSign::into(policy);
+        let sign = |i: usize, msg: &str| signers[i].alg.sign_message(msg, &keys[i]);
+        assert_eq!(Err(String::from("0 of the 2 required co-signatures verify")), cosign.check("orb"));
+
+        cosign.add("orb", 0, sign(0, "orb")).unwrap();
+        // signing twice still counts once.
+        cosign.add("orb", 0, sign(0, "orb")).unwrap();
+        assert_eq!(1, cosign.approvals("orb"));
+        assert!(cosign.add("orb", 1, sign(1, "another orb")).is_err());
+        assert!(cosign.add("orb", 1, sign(0, "orb")).is_err());
+        assert!(cosign.add("orb", 3, sign(0, "orb")).is_err());
+        assert!(cosign.check("orb").is_err());
+
+        cosign.add("orb", 2, sign(2, "orb")).unwrap();
+        assert_eq!(Ok(()), cosign.check("orb"));
+        assert!(cosign.check("another orb").is_err());
+        // a raised threshold no longer holds.
+        cosign.policy.threshold = 3;
+        assert!(cosign.check("orb").is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use serde_derive::{Serialize, Deserialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+};
+use crate::crypto::{
+    Algorithm,
+    signer::{Sign, SignerError},
+};
+
+// the longest chain accepted, leaf and intermediates together, not counting the root.
+pub const MAX_DEPTH: usize = 8;
+const VERSION: &str = "zero_orb certificate v1";
+
+// an issuer's signed statement that puk belongs to subject, with its attributes, between not_before and
+// not_after in unix seconds. ca marks a subject that may itself issue certificates.
+// a root is a ca certificate that signs itself.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Certificate {
+    pub subject: String,
+    pub alg: Algorithm,
+    pub puk: Box<[u8]>,
+    #[serde(default)]
+    pub attributes: BTreeMap<String, String>,
+    pub not_before: u64,
+    pub not_after: u64,
+    #[serde(default)]
+    pub ca: bool,
+    pub issuer: String,
+    pub issuer_alg: Algorithm,
+    pub issuer_puk: Box<[u8]>,
+    pub sig: Box<[u8]>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CertError {
+    Empty,
+    TooLong(usize),
+    BadSignature(String),
+    Expired(String),
+    NotYetValid(String),
+    NotCa(String),
+    Broken(String),
+    UnknownRoot(String),
+}
+
+impl fmt::Display for CertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CertError::Empty => write!(f, "the certificate chain is empty"),
+            CertError::TooLong(len) => write!(f, "the certificate chain is {} long, at most {} is accepted", len, MAX_DEPTH),
+            CertError::BadSignature(subject) => write!(f, "the certificate for {} is not signed by its issuer", subject),
+            CertError::Expired(subject) => write!(f, "the certificate for {} has expired", subject),
+            CertError::NotYetValid(subject) => write!(f, "the certificate for {} is not yet valid", subject),
+            CertError::NotCa(subject) => write!(f, "{} is not allowed to issue certificates", subject),
+            CertError::Broken(subject) => write!(f, "the certificate for {} is not issued by the next in the chain", subject),
+            CertError::UnknownRoot(subject) => write!(f, "the chain ends at {}, which is not issued by a trusted root", subject),
+        }
+    }
+}
+
+impl std::error::Error for CertError {}
+
+impl Certificate {
+    // an unsigned certificate, issued with sign.
+    pub fn into(
+        subject: &str,
+        alg: Algorithm,
+        puk: Box<[u8]>,
+        attributes: BTreeMap<String, String>,
+        not_before: u64,
+        not_after: u64,
+        ca: bool,
+    ) -> Self {
+        Certificate {
+            subject: subject.to_string(),
+            alg: alg,
+            puk: puk,
+            attributes: attributes,
+            not_before: not_before,
+            not_after: not_after,
+            ca: ca,
+            issuer: String::new(),
+            issuer_alg: alg,
+            issuer_puk: Vec::new().into_boxed_slice(),
+            sig: Vec::new().into_boxed_slice(),
+        }
+    }
+
+    pub fn sign<S: Sign>(mut self, issuer: &str, signer: &mut S) -> Result<Self, SignerError> {
+        self.issuer = issuer.to_string();
+        self.issuer_alg = signer.algorithm();
+        self.issuer_puk = signer.public_key();
+        self.sig = signer.sign(self.message().as_bytes())?;
+        Ok(self)
+    }
+
+    // everything but the signature, attributes in key order so the encoding is canonical.
+    pub fn message(&self) -> String {
+        serde_json::to_string(&(
+            VERSION,
+            &self.subject,
+            self.alg,
+            &self.puk,
+            &self.attributes,
+            self.not_before,
+            self.not_after,
+            self.ca,
+            &self.issuer,
+            self.issuer_alg,
+            &self.issuer_puk,
+        )).expect("Certificate::message() serializing the signed fields")
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        self.issuer_alg.verify_signature(self.message(), &self.sig, &self.issuer_puk)
+    }
+
+    fn issued_by(&self, issuer: &Certificate) -> bool {
+        self.issuer == issuer.subject && self.issuer_alg == issuer.alg && self.issuer_puk == issuer.puk
+    }
+
+    fn check_at(&self, at: u64) -> Result<(), CertError> {
+        if at < self.not_before {
+            return Err(CertError::NotYetValid(self.subject.clone()))
+        }
+        if at > self.not_after {
+            return Err(CertError::Expired(self.subject.clone()))
+        }
+        match self.verify_signature() {
+            true => Ok(()),
+            false => Err(CertError::BadSignature(self.subject.clone())),
+        }
+    }
+}
+
+// checks chain, leaf first, up to one of the roots at time at, returning the leaf.
+// each certificate must be signed by the next, every issuer must be a ca and valid at the time,
+// and the last must be issued by a root. the root itself may be left off the end of the chain.
+pub fn verify_chain<'a>(chain: &'a [Certificate], roots: &[Certificate], at: u64) -> Result<&'a Certificate, CertError> {
+    let len = chain.iter().rposition(|cert| !roots.contains(cert)).map_or(0, |last| last + 1);
+    let chain = &chain[..len];
+    if chain.is_empty() {
+        return Err(CertError::Empty)
+    }
+    if chain.len() > MAX_DEPTH {
+        return Err(CertError::TooLong(chain.len()))
+    }
+    for (i, cert) in chain.iter().enumerate() {
+        cert.check_at(at)?;
+        match chain.get(i + 1) {
+            Some(issuer) if !cert.issued_by(issuer) => return Err(CertError::Broken(cert.subject.clone())),
+            Some(issuer) if !issuer.ca => return Err(CertError::NotCa(issuer.subject.clone())),
+            Some(_) => (),
+            None => {
+                let root = roots.iter()
+                    .find(|root| cert.issued_by(root))
+                    .ok_or(CertError::UnknownRoot(cert.subject.clone()))?;
+                if !root.ca || !root.issued_by(root) {
+                    return Err(CertError::NotCa(root.subject.clone()))
+                }
+                root.check_at(at)?;
+            },
+        }
+    }
+    Ok(&chain[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::crypto::{
+        Algorithm,
+        cert::{Certificate, CertError, verify_chain},
+        keystore::KeyHandle,
+    };
+
+    #[test]
+    fn test_certificate_chain() {
+        let (mut root_key, mut ca_key, leaf_key) = (
+            KeyHandle::generate("root", Algorithm::EcdsaP384),
+            KeyHandle::generate("ca", Algorithm::Ed25519),
+            KeyHandle::generate("leaf", Algorithm::EcdsaP256),
+        );
+        let cert = |subject: &str, key: &KeyHandle, ca: bool| Certificate::into(
+            subject, key.algorithm(), key.public_key(), BTreeMap::new(), 100, 200, ca
+        );
+        let root = cert("root", &root_key, true).sign("root", &mut root_key.clone()).unwrap();
+        let ca = cert("clinic", &ca_key, true).sign("root", &mut root_key).unwrap();
+        let mut attributes = BTreeMap::new();
+        attributes.insert(String::from("role"), String::from("clinician"));
+        let leaf = Certificate::into("dr who", leaf_key.algorithm(), leaf_key.public_key(), attributes, 120, 180, false)
+            .sign("clinic", &mut ca_key)
+            .unwrap();
+        let roots = vec![root.clone()];
+        let chain = vec![leaf.clone(), ca.clone()];
+
+        assert_eq!("dr who", verify_chain(&chain, &roots, 150).unwrap().subject);
+        assert_eq!(Ok(&leaf), verify_chain(&[leaf.clone(), ca.clone(), root.clone()], &roots, 150));
+        assert_eq!(Ok(&ca), verify_chain(&[ca.clone()], &roots, 150));
+        assert_eq!(Err(CertError::NotYetValid(String::from("dr who"))), verify_chain(&chain, &roots, 110));
+        assert_eq!(Err(CertError::Expired(String::from("dr who"))), verify_chain(&chain, &roots, 190));
+        assert_eq!(Err(CertError::UnknownRoot(String::from("dr who"))), verify_chain(&[leaf.clone()], &roots, 150));
+        assert_eq!(Err(CertError::UnknownRoot(String::from("clinic"))), verify_chain(&chain, &[], 150));
+        assert_eq!(Err(CertError::Empty), verify_chain(&[], &roots, 150));
+        assert_eq!(Err(CertError::Empty), verify_chain(&[root.clone()], &roots, 150));
+
+        // edits after signing break the signature.
+        let mut promoted = leaf.clone();
+        promoted.attributes.insert(String::from("role"), String::from("auditor"));
+        assert_eq!(Err(CertError::BadSignature(String::from("dr who"))), verify_chain(&[promoted, ca.clone()], &roots, 150));
+        // a leaf cannot issue.
+        let mut sub_key = leaf_key.clone();
+        let sub = cert("intern", &ca_key, false).sign("dr who", &mut sub_key).unwrap();
+        assert_eq!(Err(CertError::NotCa(String::from("dr who"))), verify_chain(&[sub, leaf.clone(), ca.clone()], &roots, 150));
+        // nor can an issuer be swapped out.
+        let rogue = cert("clinic", &KeyHandle::generate("rogue", Algorithm::Ed25519), true).sign("root", &mut root_key).unwrap();
+        assert_eq!(Err(CertError::Broken(String::from("dr who"))), verify_chain(&[leaf.clone(), rogue], &roots, 150));
+    }
+}
@@ -0,0 +1,220 @@
+use ring::{
+    digest,
+    hmac,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde::Serialize;
+use std::{
+    fmt,
+    str::FromStr,
+};
+use crate::crypto::keystore::{KeyHandle, KeyError};
+
+// SLIP-0010 for Ed25519: every step is hardened, so a derived public key says nothing about its siblings,
+// and nothing can be derived from a public key alone.
+pub const HARDENED: u32 = 0x8000_0000;
+// the first step of every orb path, "zo" in ascii, keeping orb keys apart from others derived from the same seed.
+pub const PURPOSE: u32 = 0x7a6f;
+const CURVE: &[u8] = b"ed25519 seed";
+
+// a derivation path such as m/31343'/0'/7', each index below HARDENED and taken hardened.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Path(Vec<u32>);
+
+impl Path {
+    pub fn into(indices: Vec<u32>) -> Result<Self, String> {
+        match indices.iter().find(|&&i| i >= HARDENED) {
+            Some(i) => Err(format!("index {} is out of range, they run below {}", i, HARDENED)),
+            None => Ok(Path(indices)),
+        }
+    }
+
+    // the key for one circuit on one device: m/PURPOSE'/device'/circuit'.
+    pub fn orb(device: u32, circuit: u32) -> Result<Self, String> {
+        Self::into(vec![PURPOSE, device, circuit])
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for Path {
+    type Err = String;
+
+    // the ' or h marking a hardened step is optional, as no other kind is possible.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = s.trim().split('/');
+        if steps.next() != Some("m") {
+            return Err(format!("{} does not start at m", s))
+        }
+        steps
+            .map(|step| {
+                let index = step.trim_end_matches(|c| c == '\'' || c == 'h' || c == 'H');
+                index.parse::<u32>().map_err(|_| format!("{} is not a path step", step))
+            })
+            .collect::<Result<Vec<u32>, String>>()
+            .and_then(Self::into)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+        for i in self.0.iter() {
+            write!(f, "/{}'", i)?;
+        }
+        Ok(())
+    }
+}
+
+// a circuit's index, taken from the digest of its crs so each circuit gets its own key without a registry.
+pub fn circuit_index<C: Serialize>(crs: &C) -> u32 {
+    let json = serde_json::to_vec(crs).expect("derive::circuit_index() serializing the crs");
+    let hash = digest::digest(&digest::SHA256, &json);
+    let mut index = [0u8; 4];
+    index.copy_from_slice(&hash.as_ref()[..4]);
+    u32::from_be_bytes(index) & !HARDENED
+}
+
+// a secret key with the chain code that derives its children.
+#[derive(Clone)]
+pub struct ExtendedKey {
+    key: [u8; 32],
+    chain_code: [u8; 32],
+    depth: usize,
+}
+
+impl ExtendedKey {
+    // seeds of 16 to 64 bytes, as BIP-39 mnemonics produce.
+    pub fn master(seed: &[u8]) -> Result<Self, KeyError> {
+        if seed.len() < 16 || seed.len() > 64 {
+            return Err(KeyError::Malformed(format!("a {} byte seed, expected 16 to 64", seed.len())))
+        }
+        Ok(Self::split(hmac::sign(&hmac::SigningKey::new(&digest::SHA512, CURVE), seed).as_ref(), 0))
+    }
+
+    pub fn child(&self, index: u32) -> Self {
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&self.key);
+        data.extend_from_slice(&(index | HARDENED).to_be_bytes());
+        Self::split(hmac::sign(&hmac::SigningKey::new(&digest::SHA512, &self.chain_code), &data).as_ref(), self.depth + 1)
+    }
+
+    pub fn derive(&self, path: &Path) -> Self {
+        path.0.iter().fold(self.clone(), |key, &i| key.child(i))
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn chain_code(&self) -> &[u8] {
+        &self.chain_code
+    }
+
+    // the Ed25519 seed, the 32 bytes ring expands into the signing key.
+    pub fn secret(&self) -> &[u8] {
+        &self.key
+    }
+
+    pub fn public_key(&self) -> Box<[u8]> {
+        Ed25519KeyPair::from_seed_unchecked(untrusted::Input::from(&self.key))
+            .expect("ExtendedKey::public_key() any 32 bytes are an Ed25519 seed")
+            .public_key()
+            .as_ref()
+            .to_vec()
+            .into_boxed_slice()
+    }
+
+    pub fn key_handle(&self, name: &str) -> KeyHandle {
+        KeyHandle::from_seed_and_public_key(name, &self.key, &self.public_key())
+            .expect("ExtendedKey::key_handle() the public key comes from the seed")
+    }
+
+    fn split(i: &[u8], depth: usize) -> Self {
+        let mut key = [0u8; 32];
+        let mut chain_code = [0u8; 32];
+        key.copy_from_slice(&i[..32]);
+        chain_code.copy_from_slice(&i[32..]);
+        ExtendedKey {
+            key: key,
+            chain_code: chain_code,
+            depth: depth,
+        }
+    }
+}
+
+// the public keys along paths from one seed, for handing to a verifier's trust policy.
+pub fn public_keys(seed: &[u8], paths: &[Path]) -> Result<Vec<(Path, Box<[u8]>)>, KeyError> {
+    let master = ExtendedKey::master(seed)?;
+    Ok(paths.iter()
+        .map(|path| (path.clone(), master.derive(path).public_key()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::crypto::{
+        Algorithm,
+        derive::{Path, ExtendedKey, HARDENED, public_keys},
+    };
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect()
+    }
+
+    // the Ed25519 vectors published with SLIP-0010: path, chain code, secret, public key without its 00 prefix.
+    const VECTOR_1: [(&str, &str, &str, &str); 6] = [
+        ("m", "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            "a4b2856bfec510abab89753fac1ac0e1112364e7d250545963f135f2a33188ed"),
+        ("m/0'", "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            "8c8a13df77a28f3445213a0f432fde644acaa215fc72dcdf300d5efaa85d350c"),
+        ("m/0'/1'", "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+            "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+            "1932a5270f335bed617d5b935c80aedb1a35bd9fc1e31acafd5372c30f5c1187"),
+        ("m/0'/1'/2'", "2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+            "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+            "ae98736566d30ed0e9d2f4486a64bc95740d89c7db33f52121f8ea8f76ff0fc1"),
+        ("m/0'/1'/2'/2'", "8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+            "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+            "8abae2d66361c879b900d204ad2cc4984fa2aa344dd7ddc46007329ac76c429c"),
+        ("m/0'/1'/2'/2'/1000000000'", "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            "3c24da049451555d51a7014a37337aa4e12d41e485abccfa46b47dfb2af54b7a"),
+    ];
+
+    #[test]
+    fn test_derive() {
+        let master = ExtendedKey::master(&unhex("000102030405060708090a0b0c0d0e0f")).unwrap();
+        for (path, chain_code, secret, public) in VECTOR_1.iter() {
+            let key = master.derive(&path.parse::<Path>().unwrap());
+            assert_eq!(unhex(chain_code), key.chain_code());
+            assert_eq!(unhex(secret), key.secret());
+            assert_eq!(unhex(public), &*key.public_key());
+        }
+        let seed = unhex("fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542");
+        let key = ExtendedKey::master(&seed).unwrap().derive(&"m/0H/2147483647H/1H/2147483646H/2H".parse().unwrap());
+        assert_eq!(5, key.depth());
+        assert_eq!(unhex("5d70af781f3a37b829f0d060924d5e960bdc02e85423494afc0b1a41bbe196d4"), key.chain_code());
+        assert_eq!(unhex("551d333177df541ad876a60ea71f00447931c0a9da16f227c11ea080d7391b8d"), key.secret());
+
+        // the derived key signs like any other, and exporting it gives the same public key.
+        let path = Path::orb(3, 12).unwrap();
+        assert_eq!("m/31343'/3'/12'", path.to_string());
+        let handle = master.derive(&path).key_handle("device-3");
+        let sig = Algorithm::Ed25519.sign_message("orb", &handle.to_der().to_vec().into_boxed_slice());
+        let exported = public_keys(&unhex("000102030405060708090a0b0c0d0e0f"), &[path.clone()]).unwrap();
+        assert_eq!(vec![(path, handle.public_key())], exported);
+        assert!(Algorithm::Ed25519.verify_signature("orb", &sig, &exported[0].1));
+
+        assert!("0'/1'".parse::<Path>().is_err());
+        assert!("m/x".parse::<Path>().is_err());
+        assert!(Path::into(vec![HARDENED]).is_err());
+        assert_eq!(Ok(Path::default()), "m".parse::<Path>());
+        assert!(ExtendedKey::master(&[0u8; 8]).is_err());
+    }
+}
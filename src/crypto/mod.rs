@@ -1,8 +1,21 @@
+pub mod keystore;
+pub mod signer;
+pub mod cert;
+pub mod derive;
+pub mod x25519;
+
 use ring::{
     rand,
     signature, 
     signature::KeyPair,
     signature::Ed25519KeyPair,
+    signature::{EcdsaKeyPair, EcdsaSigningAlgorithm, EcdsaVerificationAlgorithm},
+};
+use serde_derive::{Serialize, Deserialize};
+use std::{
+    fmt,
+    marker::PhantomData,
+    str::FromStr,
 };
 
 // Ring API has adjusted for the most recent update and the method public_key_bytes() is apparently deprecated,
@@ -83,9 +96,203 @@ where
     }    
 }
 
+// the curves ring signs on, each with a fixed width signature over its own digest.
+pub trait Curve {
+    fn signing() -> &'static EcdsaSigningAlgorithm;
+    fn verification() -> &'static EcdsaVerificationAlgorithm;
+}
+
+pub struct P256;
+pub struct P384;
+
+impl Curve for P256 {
+    fn signing() -> &'static EcdsaSigningAlgorithm {
+        &signature::ECDSA_P256_SHA256_FIXED_SIGNING
+    }
+    fn verification() -> &'static EcdsaVerificationAlgorithm {
+        &signature::ECDSA_P256_SHA256_FIXED
+    }
+}
+
+impl Curve for P384 {
+    fn signing() -> &'static EcdsaSigningAlgorithm {
+        &signature::ECDSA_P384_SHA384_FIXED_SIGNING
+    }
+    fn verification() -> &'static EcdsaVerificationAlgorithm {
+        &signature::ECDSA_P384_SHA384_FIXED
+    }
+}
+
+pub struct EcDSA<T, C>(T, PhantomData<C>);
+
+impl<T, C> EcDSA<T, C> 
+where
+    T: AsRef<[u8]>,
+    C: Curve,
+{
+    pub fn into(s: T) -> Self {
+        EcDSA(s, PhantomData::<C>)
+    }
+}
+
+// the key pair is PKCS#8 as ring generates it, the public key is the uncompressed point.
+impl<T, C> SignatureScheme for EcDSA<T, C> 
+where
+    T: AsRef<[u8]>,
+    C: Curve,
+{
+    type Signature = Box<[u8]>;
+    type KeyPair = Box<[u8]>;
+    type PublicKey = Box<[u8]>;
+
+    fn sign_message(&self, key: &Self::KeyPair) -> Self::Signature {
+        EcdsaKeyPair::from_pkcs8(
+            C::signing(),
+            untrusted::Input::from(&key)
+        ).expect("EcDSA::SignatureScheme::sign_message()::from_pkcs8() panicked")
+            .sign(
+                &rand::SystemRandom::new(),
+                untrusted::Input::from(self.0.as_ref())
+            ).expect("EcDSA::SignatureScheme::sign_message()::sign() panicked")
+            .as_ref()
+            .to_vec()
+            .into_boxed_slice()
+    }
+    fn verify_signature(&self, sig: &Self::Signature, key: &Self::PublicKey) -> bool {
+        match signature::verify(
+            C::verification(), 
+            untrusted::Input::from(
+                key
+            ),
+            untrusted::Input::from(
+                &self.0.as_ref()
+            ), 
+            untrusted::Input::from(
+                sig.as_ref()
+            ),
+        ) {
+            Ok(_) => true,
+            Err(_) => false,
+        }
+    }
+    fn public_key(key: &Self::KeyPair) -> Self::PublicKey {
+        EcdsaKeyPair::from_pkcs8(
+            C::signing(),
+            untrusted::Input::from(&key)
+        ).expect("EcDSA::SignatureScheme::public_key()::from_pkcs8() panicked")
+            .public_key()
+            .as_ref()
+            .to_vec()
+            .into_boxed_slice()
+    }
+    fn init_key_pair() -> Self::KeyPair {
+        EcdsaKeyPair::generate_pkcs8(C::signing(), &rand::SystemRandom::new())
+            .expect("EcDSA::SignatureScheme::init_key_pair() panicked at the associated function: generate_pkcs8()")
+            .as_ref()
+            .to_vec()
+            .into_boxed_slice()
+    }
+}
+
+// names the scheme a BackPack was signed with, so a verifier can check orbs from any of them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Ed25519,
+    EcdsaP256,
+    EcdsaP384,
+}
+
+impl Algorithm {
+    // a PKCS#8 document names its own algorithm, so the key alone decides the scheme.
+    pub fn of_key(key: &[u8]) -> Option<Self> {
+        let input = untrusted::Input::from(key);
+        if Ed25519KeyPair::from_pkcs8(input).is_ok() {
+            return Some(Algorithm::Ed25519)
+        }
+        if EcdsaKeyPair::from_pkcs8(P256::signing(), input).is_ok() {
+            return Some(Algorithm::EcdsaP256)
+        }
+        if EcdsaKeyPair::from_pkcs8(P384::signing(), input).is_ok() {
+            return Some(Algorithm::EcdsaP384)
+        }
+        None
+    }
+
+    pub fn init_key_pair(&self) -> Box<[u8]> {
+        match self {
+            Algorithm::Ed25519 => EdDSA::<String>::init_key_pair(),
+            Algorithm::EcdsaP256 => EcDSA::<String, P256>::init_key_pair(),
+            Algorithm::EcdsaP384 => EcDSA::<String, P384>::init_key_pair(),
+        }
+    }
+
+    pub fn sign_message<M: AsRef<[u8]>>(&self, msg: M, key: &Box<[u8]>) -> Box<[u8]> {
+        match self {
+            Algorithm::Ed25519 => EdDSA::into(msg).sign_message(key),
+            Algorithm::EcdsaP256 => EcDSA::<M, P256>::into(msg).sign_message(key),
+            Algorithm::EcdsaP384 => EcDSA::<M, P384>::into(msg).sign_message(key),
+        }
+    }
+
+    pub fn verify_signature<M: AsRef<[u8]>>(&self, msg: M, sig: &Box<[u8]>, key: &Box<[u8]>) -> bool {
+        match self {
+            Algorithm::Ed25519 => EdDSA::into(msg).verify_signature(sig, key),
+            Algorithm::EcdsaP256 => EcDSA::<M, P256>::into(msg).verify_signature(sig, key),
+            Algorithm::EcdsaP384 => EcDSA::<M, P384>::into(msg).verify_signature(sig, key),
+        }
+    }
+
+    pub fn public_key(&self, key: &Box<[u8]>) -> Box<[u8]> {
+        match self {
+            Algorithm::Ed25519 => EdDSA::<String>::public_key(key),
+            Algorithm::EcdsaP256 => EcDSA::<String, P256>::public_key(key),
+            Algorithm::EcdsaP384 => EcDSA::<String, P384>::public_key(key),
+        }
+    }
+
+    pub fn signature_len(&self) -> usize {
+        match self {
+            Algorithm::Ed25519 => 64,
+            Algorithm::EcdsaP256 => 64,
+            Algorithm::EcdsaP384 => 96,
+        }
+    }
+
+    pub fn public_key_len(&self) -> usize {
+        match self {
+            Algorithm::Ed25519 => 32,
+            Algorithm::EcdsaP256 => 65,
+            Algorithm::EcdsaP384 => 97,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Algorithm::Ed25519 => write!(f, "ed25519"),
+            Algorithm::EcdsaP256 => write!(f, "p256"),
+            Algorithm::EcdsaP384 => write!(f, "p384"),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ed25519" => Ok(Algorithm::Ed25519),
+            "p256" => Ok(Algorithm::EcdsaP256),
+            "p384" => Ok(Algorithm::EcdsaP384),
+            _ => Err(format!("unknown signature algorithm {}, expected ed25519, p256 or p384", s)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::crypto::{EdDSA, SignatureScheme};
+    use crate::crypto::{EdDSA, EcDSA, P256, P384, Algorithm, SignatureScheme};
 
     #[test]
     fn test_ed_key() {
@@ -99,6 +306,55 @@ mod tests {
             )
         );
     }
-}
 
+    #[test]
+    fn test_ec_key() {
+        let kp = EcDSA::<String, P256>::init_key_pair();
+        let sig = EcDSA::<_, P256>::into("testing_cryo_key")
+            .sign_message(&kp);
+        assert_eq!(64, sig.len());
+        assert!(EcDSA::<_, P256>::into("testing_cryo_key")
+            .verify_signature(
+                &sig,
+                &EcDSA::<String, P256>::public_key(&kp),
+            )
+        );
+        assert!(!EcDSA::<_, P256>::into("tampered_cryo_key")
+            .verify_signature(
+                &sig,
+                &EcDSA::<String, P256>::public_key(&kp),
+            )
+        );
+        let kp = EcDSA::<String, P384>::init_key_pair();
+        let sig = EcDSA::<_, P384>::into("testing_cryo_key")
+            .sign_message(&kp);
+        assert_eq!(96, sig.len());
+        assert!(EcDSA::<_, P384>::into("testing_cryo_key")
+            .verify_signature(
+                &sig,
+                &EcDSA::<String, P384>::public_key(&kp),
+            )
+        );
+    }
 
+    #[test]
+    fn test_algorithm() {
+        for alg in vec![Algorithm::Ed25519, Algorithm::EcdsaP256, Algorithm::EcdsaP384] {
+            let kp = alg.init_key_pair();
+            assert_eq!(Some(alg), Algorithm::of_key(&kp));
+            assert_eq!(alg, alg.to_string().parse::<Algorithm>().unwrap());
+            let (sig, puk) = (alg.sign_message("message", &kp), alg.public_key(&kp));
+            assert_eq!(alg.signature_len(), sig.len());
+            assert_eq!(alg.public_key_len(), puk.len());
+            assert!(alg.verify_signature("message", &sig, &puk));
+            assert!(!alg.verify_signature("massage", &sig, &puk));
+        }
+        // a signature only verifies under the scheme that made it.
+        let kp = Algorithm::EcdsaP256.init_key_pair();
+        let sig = Algorithm::EcdsaP256.sign_message("message", &kp);
+        assert!(!Algorithm::EcdsaP384.verify_signature("message", &sig, &Algorithm::EcdsaP256.public_key(&kp)));
+        assert_eq!(None, Algorithm::of_key(&[0u8; 48]));
+        // a bare 32 byte value names no scheme, so it cannot pass for a key.
+        assert_eq!(None, Algorithm::of_key(&[7u8; 32]));
+    }
+}
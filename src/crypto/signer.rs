@@ -0,0 +1,441 @@
+use serde_derive::{Serialize, Deserialize};
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use crate::crypto::{
+    Algorithm,
+    keystore::KeyHandle,
+};
+
+// whatever holds the private key. Andromeda hands it the payload and only ever sees the signature,
+// so the key can stay in another process.
+pub trait Sign {
+    fn algorithm(&self) -> Algorithm;
+    fn public_key(&self) -> Box<[u8]>;
+    fn sign(&mut self, payload: &[u8]) -> Result<Box<[u8]>, SignerError>;
+}
+
+#[derive(Debug)]
+pub enum SignerError {
+    Io(io::Error),
+    Refused(String),
+    Malformed(String),
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignerError::Io(e) => write!(f, "talking to the signer failed: {}", e),
+            SignerError::Refused(msg) => write!(f, "the signer refused: {}", msg),
+            SignerError::Malformed(msg) => write!(f, "the signer answered with {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SignerError {}
+
+impl From<io::Error> for SignerError {
+    fn from(e: io::Error) -> Self {
+        SignerError::Io(e)
+    }
+}
+
+// a key held in this process, as Andromeda always did.
+impl Sign for KeyHandle {
+    fn algorithm(&self) -> Algorithm {
+        KeyHandle::algorithm(self)
+    }
+    fn public_key(&self) -> Box<[u8]> {
+        KeyHandle::public_key(self)
+    }
+    fn sign(&mut self, payload: &[u8]) -> Result<Box<[u8]>, SignerError> {
+        Ok(KeyHandle::algorithm(self).sign_message(payload, &self.to_der().to_vec().into_boxed_slice()))
+    }
+}
+
+// a closure standing in for the key, for an HSM or remote API the caller already talks to.
+pub struct Callback<F> {
+    alg: Algorithm,
+    puk: Box<[u8]>,
+    f: F,
+}
+
+impl<F> Callback<F>
+where
+    F: FnMut(&[u8]) -> Result<Box<[u8]>, SignerError>
+{
+    pub fn into(alg: Algorithm, puk: Box<[u8]>, f: F) -> Self {
+        Callback {
+            alg: alg,
+            puk: puk,
+            f: f,
+        }
+    }
+}
+
+impl<F> Sign for Callback<F>
+where
+    F: FnMut(&[u8]) -> Result<Box<[u8]>, SignerError>
+{
+    fn algorithm(&self) -> Algorithm {
+        self.alg
+    }
+    fn public_key(&self) -> Box<[u8]> {
+        self.puk.clone()
+    }
+    fn sign(&mut self, payload: &[u8]) -> Result<Box<[u8]>, SignerError> {
+        checked(self.alg, &self.puk, payload, (self.f)(payload)?)
+    }
+}
+
+// the pipe protocol is one json object per line each way. bytes travel as base64.
+// the daemon answers public_key with its scheme and key, and sign with a signature, or either with an error.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request {
+    PublicKey,
+    Sign { payload: String },
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Response {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    alg: Option<Algorithm>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    puk: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sig: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+// how long a child daemon has to answer a request, and to exit once its stdin is closed.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+const GRACE: Duration = Duration::from_secs(2);
+
+// talks to a signing daemon over a pair of pipes, usually the stdin and stdout of a child process.
+// the scheme and public key are asked for once, up front. a child that does not answer in time is killed,
+// which ends the read, and the signer is then spent: a late answer could otherwise be taken for the next one.
+// other pipes are read as they are, so give them a read timeout of their own.
+pub struct PipeSigner {
+    pipe: Pipe,
+    alg: Algorithm,
+    puk: Box<[u8]>,
+}
+
+// the pipes to the daemon, and the child behind them if there is one.
+struct Pipe {
+    reader: Box<dyn BufRead + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Option<Arc<Mutex<Child>>>,
+    timeout: Duration,
+    spent: bool,
+}
+
+impl PipeSigner {
+    pub fn into<R, W>(reader: R, writer: W) -> Result<Self, SignerError>
+    where
+        R: BufRead + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        Self::start(Box::new(reader), Box::new(writer), None, TIMEOUT)
+    }
+
+    // starts the daemon as a child process and signs through its stdin and stdout.
+    pub fn spawn(program: &str, args: &[String]) -> Result<Self, SignerError> {
+        Self::spawn_timeout(program, args, TIMEOUT)
+    }
+
+    pub fn spawn_timeout(program: &str, args: &[String], timeout: Duration) -> Result<Self, SignerError> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => Self::start(
+                Box::new(BufReader::new(stdout)),
+                Box::new(stdin),
+                Some(Arc::new(Mutex::new(child))),
+                timeout
+            ),
+            (_, _) => {
+                stop(&mut child, Duration::from_secs(0));
+                Err(SignerError::Io(io::Error::new(io::ErrorKind::BrokenPipe, "the signer has no stdio")))
+            },
+        }
+    }
+
+    // the signer is only built once the daemon has named its scheme and key.
+    // a pipe that fails here is dropped, which stops its child.
+    fn start(
+        reader: Box<dyn BufRead + Send>,
+        writer: Box<dyn Write + Send>,
+        child: Option<Arc<Mutex<Child>>>,
+        timeout: Duration
+    ) -> Result<Self, SignerError> {
+        let mut pipe = Pipe {
+            reader: reader,
+            writer: writer,
+            child: child,
+            timeout: timeout,
+            spent: false,
+        };
+        let response = pipe.ask(&Request::PublicKey)?;
+        match (response.alg, response.puk) {
+            (Some(alg), Some(puk)) => {
+                let puk = decode("puk", &puk)?;
+                if puk.len() != alg.public_key_len() {
+                    return Err(SignerError::Malformed(format!("a {} byte public key for {}", puk.len(), alg)))
+                }
+                Ok(PipeSigner {
+                    pipe: pipe,
+                    alg: alg,
+                    puk: puk,
+                })
+            },
+            (_, _) => Err(SignerError::Malformed(String::from("no scheme or public key"))),
+        }
+    }
+}
+
+impl Pipe {
+    fn ask(&mut self, request: &Request) -> Result<Response, SignerError> {
+        if self.spent {
+            return Err(SignerError::Io(io::Error::new(io::ErrorKind::TimedOut, "the signer timed out earlier")))
+        }
+        let line = serde_json::to_string(request).expect("Pipe::ask() serializing the request");
+        writeln!(self.writer, "{}", line)?;
+        self.writer.flush()?;
+        let watchdog = self.child.clone().map(|child| Watchdog::start(child, self.timeout));
+        let mut answer = String::new();
+        let read = self.reader.read_line(&mut answer);
+        if let Some(true) = watchdog.map(Watchdog::finish) {
+            self.spent = true;
+            return Err(SignerError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("the signer did not answer within {}s", self.timeout.as_secs())
+            )))
+        }
+        if read? == 0 {
+            return Err(SignerError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "the signer hung up")))
+        }
+        let response: Response = serde_json::from_str(&answer)
+            .map_err(|e| SignerError::Malformed(format!("a response that does not parse: {}", e)))?;
+        match response.error {
+            Some(msg) => Err(SignerError::Refused(msg)),
+            None => Ok(response),
+        }
+    }
+}
+
+// kills the child unless finished before the timeout, and reports whether it did.
+struct Watchdog {
+    done: mpsc::Sender<()>,
+    fired: thread::JoinHandle<bool>,
+}
+
+impl Watchdog {
+    fn start(child: Arc<Mutex<Child>>, timeout: Duration) -> Self {
+        let (done, wait) = mpsc::channel();
+        let fired = thread::spawn(move || match wait.recv_timeout(timeout) {
+            Err(RecvTimeoutError::Timeout) => {
+                if let Ok(mut child) = child.lock() {
+                    let _ = child.kill();
+                }
+                true
+            },
+            _ => false,
+        });
+        Watchdog {
+            done: done,
+            fired: fired,
+        }
+    }
+
+    fn finish(self) -> bool {
+        let _ = self.done.send(());
+        self.fired.join().unwrap_or(true)
+    }
+}
+
+impl Sign for PipeSigner {
+    fn algorithm(&self) -> Algorithm {
+        self.alg
+    }
+    fn public_key(&self) -> Box<[u8]> {
+        self.puk.clone()
+    }
+    fn sign(&mut self, payload: &[u8]) -> Result<Box<[u8]>, SignerError> {
+        let response = self.pipe.ask(&Request::Sign { payload: base64::encode(payload) })?;
+        match response.sig {
+            Some(sig) => checked(self.alg, &self.puk, payload, decode("sig", &sig)?),
+            None => Err(SignerError::Malformed(String::from("no signature"))),
+        }
+    }
+}
+
+// closing stdin tells the daemon to exit. it is given a moment to do so, none if it has already timed out,
+// and is then killed, and waited on either way so it does not linger as a zombie.
+impl Drop for Pipe {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take() {
+            self.writer = Box::new(io::sink());
+            if let Ok(mut child) = child.lock() {
+                stop(&mut child, match self.spent {
+                    true => Duration::from_secs(0),
+                    false => GRACE,
+                });
+            }
+        }
+    }
+}
+
+fn stop(child: &mut Child, grace: Duration) {
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Ok(Some(_)) | Err(_) => return,
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// the daemon side: answers requests from reader on writer until reader closes.
+// a request that cannot be read is answered with an error rather than ending the session.
+pub fn serve<S, R, W>(signer: &mut S, reader: R, mut writer: W) -> Result<(), SignerError>
+where
+    S: Sign,
+    R: BufRead,
+    W: Write,
+{
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let response = match serde_json::from_str::<Request>(&line) {
+            Ok(Request::PublicKey) => Response {
+                alg: Some(signer.algorithm()),
+                puk: Some(base64::encode(&signer.public_key())),
+                ..Response::default()
+            },
+            Ok(Request::Sign { payload }) => match base64::decode(&payload) {
+                Ok(payload) => match signer.sign(&payload) {
+                    Ok(sig) => Response {
+                        sig: Some(base64::encode(&sig)),
+                        ..Response::default()
+                    },
+                    Err(e) => Response {
+                        error: Some(e.to_string()),
+                        ..Response::default()
+                    },
+                },
+                Err(e) => Response {
+                    error: Some(format!("the payload is not base64: {}", e)),
+                    ..Response::default()
+                },
+            },
+            Err(e) => Response {
+                error: Some(format!("the request does not parse: {}", e)),
+                ..Response::default()
+            },
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response).expect("signer::serve() serializing the response"))?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+fn decode(field: &str, text: &str) -> Result<Box<[u8]>, SignerError> {
+    base64::decode(text)
+        .map(Vec::into_boxed_slice)
+        .map_err(|e| SignerError::Malformed(format!("a {} that is not base64: {}", field, e)))
+}
+
+// a signature from outside is checked before it is used, so a confused signer fails here and not at the verifier.
+fn checked(alg: Algorithm, puk: &Box<[u8]>, payload: &[u8], sig: Box<[u8]>) -> Result<Box<[u8]>, SignerError> {
+    match alg.verify_signature(payload, &sig, puk) {
+        true => Ok(sig),
+        false => Err(SignerError::Malformed(String::from("a signature that does not match its public key"))),
+    }
+}
+
+// the daemons here run over a UnixStream pair or as sh and sleep children, so only on unix.
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        io::{self, BufReader},
+        os::unix::net::UnixStream,
+        thread,
+        time::{Duration, Instant},
+    };
+    use crate::crypto::{
+        Algorithm,
+        keystore::KeyHandle,
+        signer::{Sign, SignerError, Callback, PipeSigner, serve},
+    };
+
+    #[test]
+    fn test_pipe_signer() {
+        let key = KeyHandle::generate("daemon", Algorithm::EcdsaP256);
+        let puk = key.public_key();
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        let daemon = thread::spawn(move || {
+            let mut key = key;
+            serve(&mut key, BufReader::new(theirs.try_clone().unwrap()), theirs).unwrap();
+        });
+        let mut signer = PipeSigner::into(BufReader::new(ours.try_clone().unwrap()), ours).unwrap();
+        assert_eq!((Algorithm::EcdsaP256, puk.clone()), (signer.algorithm(), signer.public_key()));
+        let sig = signer.sign(b"testing_cryo_key").unwrap();
+        assert!(Algorithm::EcdsaP256.verify_signature(&b"testing_cryo_key"[..], &sig, &puk));
+        drop(signer);
+        daemon.join().unwrap();
+
+        // a daemon answering with someone else's signature is caught on this side.
+        let other = Algorithm::EcdsaP256.init_key_pair();
+        let mut liar = Callback::into(Algorithm::EcdsaP256, puk, |payload: &[u8]| Ok(Algorithm::EcdsaP256.sign_message(payload, &other)));
+        match liar.sign(b"testing_cryo_key") {
+            Err(SignerError::Malformed(_)) => (),
+            _ => panic!("a signature from the wrong key was accepted"),
+        }
+    }
+
+    #[test]
+    fn test_pipe_timeout() {
+        let timed_out = |result: Result<PipeSigner, SignerError>| match result {
+            Err(SignerError::Io(ref e)) => e.kind() == io::ErrorKind::TimedOut,
+            _ => false,
+        };
+        // a child that never answers is killed rather than hanging prove.
+        let start = Instant::now();
+        assert!(timed_out(PipeSigner::spawn_timeout("sleep", &[String::from("30")], Duration::from_millis(100))));
+
+        // nor is one that hangs after handing over its key: the signer is spent, and dropping it kills the child.
+        let key = KeyHandle::generate("daemon", Algorithm::Ed25519);
+        let script = format!(
+            "read request; echo '{{\"alg\":\"Ed25519\",\"puk\":\"{}\"}}'; exec sleep 30",
+            base64::encode(&key.public_key())
+        );
+        let mut hung = PipeSigner::spawn_timeout("sh", &[String::from("-c"), script], Duration::from_millis(500)).unwrap();
+        assert_eq!(key.public_key(), hung.public_key());
+        for _ in 0..2 {
+            match hung.sign(b"testing_cryo_key") {
+                Err(SignerError::Io(ref e)) if e.kind() == io::ErrorKind::TimedOut => (),
+                _ => panic!("a hung signer did not time out"),
+            }
+        }
+        drop(hung);
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,95 @@
+use ring::rand::{SecureRandom, SystemRandom};
+use x25519_dalek::{PublicKey, StaticSecret};
+use crate::crypto::keystore::KeyError;
+
+// a long-lived X25519 key that BackPacks can be sealed to.
+// ring only agrees with ephemeral keys, so the recipient's side is x25519-dalek's static secret.
+#[derive(Clone)]
+pub struct SealKey {
+    secret: StaticSecret,
+}
+
+impl SealKey {
+    pub fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        SystemRandom::new().fill(&mut secret).expect("SealKey::generate() filling the secret from SystemRandom");
+        SealKey {
+            secret: StaticSecret::from(secret),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeyError> {
+        if bytes.len() != 32 {
+            return Err(KeyError::Malformed(format!("a {} byte X25519 secret, expected 32", bytes.len())))
+        }
+        let mut secret = [0u8; 32];
+        secret.copy_from_slice(bytes);
+        Ok(SealKey {
+            secret: StaticSecret::from(secret),
+        })
+    }
+
+    pub fn to_bytes(&self) -> &[u8] {
+        self.secret.as_bytes()
+    }
+
+    pub fn public_key(&self) -> Box<[u8]> {
+        Box::new(PublicKey::from(&self.secret).to_bytes())
+    }
+
+    // the shared secret with peer, or None for a malformed key or one of small order, whose secret is all zero.
+    pub fn agree(&self, peer: &[u8]) -> Option<[u8; 32]> {
+        if peer.len() != 32 {
+            return None
+        }
+        let mut u = [0u8; 32];
+        u.copy_from_slice(peer);
+        let shared = self.secret.diffie_hellman(&PublicKey::from(u));
+        match shared.was_contributory() {
+            true => Some(*shared.as_bytes()),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::{agreement, rand::SystemRandom};
+    use crate::crypto::x25519::SealKey;
+
+    fn bytes(s: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_x25519() {
+        // RFC 7748, 6.1.
+        let alice = SealKey::from_bytes(&bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")).unwrap();
+        let bob = SealKey::from_bytes(&bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb")).unwrap();
+        assert_eq!(&bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")[..], &*alice.public_key());
+        assert_eq!(&bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")[..], &*bob.public_key());
+        let shared = bytes("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(Some(shared), alice.agree(&bob.public_key()));
+        assert_eq!(Some(shared), bob.agree(&alice.public_key()));
+        assert_eq!(alice.to_bytes(), SealKey::from_bytes(alice.to_bytes()).unwrap().to_bytes());
+        assert!(SealKey::from_bytes(&[7u8; 31]).is_err());
+        // a point of small order gives nothing to agree on.
+        assert_eq!(None, alice.agree(&[0u8; 32]));
+        assert_eq!(None, alice.agree(&[9u8; 31]));
+
+        // and the static side agrees with ring's ephemeral side.
+        let key = SealKey::generate();
+        let ephemeral = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &SystemRandom::new()).unwrap();
+        let epk = ephemeral.compute_public_key().unwrap();
+        let theirs = agreement::agree_ephemeral(ephemeral, &agreement::X25519, untrusted::Input::from(&key.public_key()), (), |shared| {
+            let mut out = [0u8; 32];
+            out.copy_from_slice(shared);
+            Ok(out)
+        }).unwrap();
+        assert_eq!(Some(theirs), key.agree(epk.as_ref()));
+    }
+}
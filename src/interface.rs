@@ -4,6 +4,7 @@ use zksnark::{
     groth16::{
         EllipticEncryptable,
         Random,
+        circuit::{ASTParser, TryParse, dummy_rep::DummyRep},
     },
 };
 use std::{
@@ -11,18 +12,75 @@ use std::{
     ops::{Add, Sub},
     iter::Sum,
     marker::PhantomData,
+    fmt,
 };
 use crate::{
     knowledge::zkProof,
     common::Common,
-    crypto::{EdDSA, SignatureScheme},
+    crypto::{
+        Algorithm,
+        cert::Certificate,
+        keystore::KeyHandle,
+        signer::{Sign, SignerError},
+    },
+    cosign::{CoSign, Policy, Signer},
+    replay::{self, NonceStore, Window, Rejection},
+    report::{VerificationReport, Check},
+    transform::into_field::width,
+    trust::TrustPolicy,
 };
 use serde_derive::{Serialize, Deserialize};
 use serde::{Serialize, Deserialize};
+use num::BigUint;
 
-pub trait GoZero<'de> {
+pub trait GoZero<'de>: Sized {
     type Returner: Serialize + Deserialize<'de>;
-    fn go(self) -> Self::Returner;
+    // fails only when an external signer does.
+    fn try_go(self) -> Result<Self::Returner, SignerError>;
+    fn go(self) -> Self::Returner {
+        self.try_go().expect("GoZero::go() the signer failed to sign the BackPack")
+    }
+}
+
+// a BackPack as one json column per field, the way a database row holds it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Columns<S = String> {
+    pub prf: S,
+    pub ver: S,
+    pub vb: S,
+    pub vs: S,
+    pub vf: S,
+    pub ut: S,
+    pub sig: S,
+    pub puk: S,
+    pub crs: S,
+    pub iat: S,
+    pub nonce: S,
+    pub alg: S,
+    pub cosign: S,
+    pub chain: S,
+}
+
+impl<S: AsRef<[u8]>> Columns<S> {
+    // the same columns borrowed as bytes.
+    pub fn as_bytes(&self) -> Columns<&[u8]> {
+        Columns {
+            prf: self.prf.as_ref(),
+            ver: self.ver.as_ref(),
+            vb: self.vb.as_ref(),
+            vs: self.vs.as_ref(),
+            vf: self.vf.as_ref(),
+            ut: self.ut.as_ref(),
+            sig: self.sig.as_ref(),
+            puk: self.puk.as_ref(),
+            crs: self.crs.as_ref(),
+            iat: self.iat.as_ref(),
+            nonce: self.nonce.as_ref(),
+            alg: self.alg.as_ref(),
+            cosign: self.cosign.as_ref(),
+            chain: self.chain.as_ref(),
+        }
+    }
 }
 
 pub trait ZeroRef {
@@ -30,20 +88,97 @@ pub trait ZeroRef {
     fn copy_str(&self) -> Self::C;
     fn get_prf_str(&self) -> String;
     fn get_ver_str(&self) -> String;
+    fn get_vb_str(&self) -> String;
+    fn get_vs_str(&self) -> String;
+    fn get_vf_str(&self) -> String;
+    fn get_ut_str(&self) -> String;
     fn get_sig_str(&self) -> String;
     fn get_puk_str(&self) -> String;
     fn get_crs_str(&self) -> String;
+    fn get_iat_str(&self) -> String;
+    fn get_nonce_str(&self) -> String;
+    fn get_alg_str(&self) -> String;
+    fn get_cosign_str(&self) -> String;
+    fn get_chain_str(&self) -> String;
+}
+
+// the inverse of ZeroRef: rebuilds a typed orb from the json it handed out, as strings or as bytes.
+pub trait FromZeroRef: Sized {
+    type C;
+    fn from_copy(c: Self::C) -> Result<Self, PartsError>;
+    fn from_parts<S: AsRef<[u8]>>(parts: Columns<S>) -> Result<Self, PartsError>;
+}
+
+// names the column that could not be turned back into its BackPack field.
+#[derive(Debug)]
+pub enum PartsError {
+    Parse(&'static str, String),
+    Invalid(&'static str, String),
+}
+
+impl fmt::Display for PartsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartsError::Parse(field, msg) => write!(f, "BackPack field {} does not parse: {}", field, msg),
+            PartsError::Invalid(field, msg) => write!(f, "BackPack field {} is invalid: {}", field, msg),
+        }
+    }
+}
+
+impl std::error::Error for PartsError {}
+
+pub trait MarkZero: Sized {   
+    fn report(self) -> VerificationReport;
+    fn verify(self) -> bool {
+        self.report().is_valid()
+    }
+}
+
+// verification against a validity window and the nonces the verifier has already accepted.
+// the nonce is only recorded once the orb has otherwise verified, so junk cannot burn nonces.
+pub trait MarkFresh: Sized {
+    fn report_fresh<S: NonceStore>(self, store: &mut S, window: &Window, now: u64) -> VerificationReport;
+    fn verify_fresh<S: NonceStore>(self, store: &mut S, window: &Window, now: u64) -> Result<(), Rejection> {
+        let report = self.report_fresh(store, window, now);
+        match report.is_valid() {
+            true => Ok(()),
+            false => Err(Rejection::from(report.reason)),
+        }
+    }
+}
+
+// verification that first asks the verifier's trust policy whether the signing key was good at the issue time.
+// the other checks only run for a trusted key, and the report then records key as Passed.
+// the issue time is the prover's word, so it must first fall within the window around now: otherwise
+// a revoked key could backdate its orbs to before the revocation.
+pub trait MarkTrusted: Sized {
+    fn report_trusted(self, policy: &TrustPolicy, window: &Window, now: u64) -> VerificationReport;
+    fn report_trusted_fresh<S: NonceStore>(self, policy: &TrustPolicy, store: &mut S, window: &Window, now: u64) -> VerificationReport;
+    fn verify_trusted(self, policy: &TrustPolicy, window: &Window, now: u64) -> bool {
+        self.report_trusted(policy, window, now).is_valid()
+    }
 }
 
-pub trait MarkZero {   
-    fn verify(self) -> bool;
+// verification against the co-signing policy the verifier demands. an orb asking for any other policy,
+// or for none, is Unapproved before anything else is checked, since its own policy is the prover's choice.
+pub trait MarkApproved: Sized {
+    fn report_approved(self, policy: &Policy) -> VerificationReport;
+    fn verify_approved(self, policy: &Policy) -> bool {
+        self.report_approved(policy).is_valid()
+    }
 }
 
 pub struct Andromeda<A, B, T, U, V, W> {
     crs: A,
     weights: B,
     compute_out: Option<Vec<usize>>,
-    key_pair: Box<[u8]>,
+    compute_bits: Option<Vec<usize>>,
+    compute_signed: Option<Vec<isize>>,
+    compute_elements: Option<Vec<String>>,
+    tag: Option<String>,
+    signer: Box<dyn Sign>,
+    policy: Option<Policy>,
+    chain: Option<Vec<Certificate>>,
     _phantom_fr: PhantomData<T>,
     _phantom_g1: PhantomData<U>,
     _phantom_g2: PhantomData<V>,
@@ -54,51 +189,220 @@ pub struct Andromeda<A, B, T, U, V, W> {
 pub struct BackPack<A, T, U, V, W> {
     pub prf: Proof<U, V>,
     pub ver: Option<Vec<usize>>,
+    pub vb: Option<Vec<usize>>,
+    // the public signed nums, checked after the bits.
+    #[serde(default)]
+    pub vs: Option<Vec<isize>>,
+    // the public field elements in decimal, checked last.
+    #[serde(default)]
+    pub vf: Option<Vec<String>>,
+    pub ut: Option<String>,
     pub sig: Box<[u8]>,
     pub puk: Box<[u8]>,    
     pub crs: A,
+    pub iat: u64,
+    pub nonce: Box<[u8]>,
+    // required: orbs from before the scheme was recorded signed a different payload, so they no longer read.
+    pub alg: Algorithm,
+    #[serde(default)]
+    pub cosign: Option<CoSign>,
+    // leaf first. it is checked on its own merits, so it is not covered by the orb's signature.
+    #[serde(default)]
+    pub chain: Option<Vec<Certificate>>,
     _phantom_fr: PhantomData<T>,
     _phantom_gt: PhantomData<W>,
 }
 
+// compute_out is the public nums handed to the Marker.
+// key_pair is the PKCS#8 document itself or a KeyHandle from the KeyStore, and decides the signature scheme.
+// into_bits also hands the Marker the public bits compute_bits after the nums, tag setting their width as
+// it does for the bits in Knowledge. with_signed hands it the public signed nums after those, and with_elements
+// the public field elements last. into_signer takes anything that can sign instead, so the key need never
+// be in this process.
 impl<A, B, T, U, V, W> Andromeda<A, B, T, U, V, W> {
-    pub fn into(
+    pub fn into<K: Into<Box<[u8]>>>(
+        crs: A, 
+        weights: B, 
+        compute_out: Option<Vec<usize>>,
+        key_pair: K,
+    ) -> Andromeda<A, B, T, U, V, W> {
+        Self::into_bits(crs, weights, compute_out, None, None, key_pair)
+    }
+    pub fn into_bits<K: Into<Box<[u8]>>>(
         crs: A, 
         weights: B, 
         compute_out: Option<Vec<usize>>,
-        key_pair: Box<[u8]>,
+        compute_bits: Option<Vec<usize>>,
+        tag: Option<String>,
+        key_pair: K,
+    ) -> Andromeda<A, B, T, U, V, W> {
+        let key = KeyHandle::from_der("andromeda", &key_pair.into())
+            .expect("Andromeda::into_bits() key_pair is not an Ed25519, P-256 or P-384 PKCS#8 document");
+        Self::into_signer(crs, weights, compute_out, compute_bits, tag, key)
+    }
+    pub fn into_signer<S: Sign + 'static>(
+        crs: A, 
+        weights: B, 
+        compute_out: Option<Vec<usize>>,
+        compute_bits: Option<Vec<usize>>,
+        tag: Option<String>,
+        signer: S,
     ) -> Andromeda<A, B, T, U, V, W> {
         Self {
             crs: crs,
             weights: weights,
-            key_pair: key_pair,
+            signer: Box::new(signer),
+            policy: None,
+            chain: None,
             compute_out: compute_out,
+            compute_bits: compute_bits,
+            compute_signed: None,
+            compute_elements: None,
+            tag: tag,
             _phantom_fr: PhantomData::<T>,
             _phantom_g1: PhantomData::<U>,
             _phantom_g2: PhantomData::<V>,
             _phantom_gt: PhantomData::<W>, 
         }
     }
+    // the public signed nums, as the circuit declares them with CircuitBuilder::input_signed.
+    pub fn with_signed(mut self, compute_signed: Vec<isize>) -> Self {
+        self.compute_signed = Some(compute_signed);
+        self
+    }
+
+    // the public field elements, as the circuit declares them with CircuitBuilder::output_element, such as a digest.
+    pub fn with_elements(mut self, compute_elements: Vec<BigUint>) -> Self {
+        self.compute_elements = Some(compute_elements.iter().map(|f| f.to_string()).collect());
+        self
+    }
+
+    // requires threshold of the policy's signers to co-sign the orb once it has been proved.
+    pub fn cosigned(mut self, policy: Policy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    // carries the certificate chain for the signing key, leaf first, in the orb.
+    pub fn certified(mut self, chain: Vec<Certificate>) -> Self {
+        self.chain = Some(chain);
+        self
+    }
 }
 
 impl<A, T, U, V, W> BackPack<A, T, U, V, W> {
     pub fn into(
         prf: Proof<U, V>,
         ver: Option<Vec<usize>>,
+        vb: Option<Vec<usize>>,
+        vs: Option<Vec<isize>>,
+        vf: Option<Vec<String>>,
+        ut: Option<String>,
         sig: Box<[u8]>,
         puk: Box<[u8]>,    
         crs: A,
+        iat: u64,
+        nonce: Box<[u8]>,
+        alg: Algorithm,
+        cosign: Option<CoSign>,
+        chain: Option<Vec<Certificate>>,
     ) -> Self {
         BackPack {
             prf: prf,
             ver: ver,
+            vb: vb,
+            vs: vs,
+            vf: vf,
+            ut: ut,
             sig: sig,
             puk: puk,    
             crs: crs,
+            iat: iat,
+            nonce: nonce,
+            alg: alg,
+            cosign: cosign,
+            chain: chain,
             _phantom_fr: PhantomData::<T>,
             _phantom_gt: PhantomData::<W>,
         }
     }
+
+    // the message the prover's signature covers.
+    pub fn message(&self) -> String 
+    where
+        U: Serialize,
+        V: Serialize,
+    {
+        payload(&self.prf, self.iat, &self.nonce, self.alg, self.cosign.as_ref().map(|cosign| &cosign.policy))
+    }
+
+    // the message the co-signatures cover: the prover's, under a tag of its own, so that the prover's
+    // signature cannot be passed off as a co-signature from the same key.
+    pub fn cosign_message(&self) -> String 
+    where
+        U: Serialize,
+        V: Serialize,
+    {
+        format!("{}{}", COSIGN_TAG, self.message())
+    }
+
+    // co-signs with a key pair named in the policy, returning the signer's place in it.
+    pub fn cosign(&mut self, key_pair: &Box<[u8]>) -> Result<usize, String> 
+    where
+        U: Serialize,
+        V: Serialize,
+    {
+        let signer = match Signer::of_key(key_pair) {
+            Some(signer) => signer,
+            None => return Err(String::from("the key pair is not one zero_orb can sign with")),
+        };
+        let i = match self.cosign.as_ref().map(|cosign| cosign.policy.position(&signer)) {
+            Some(Some(i)) => i,
+            Some(None) => return Err(String::from("the key is not one of the orb's co-signers")),
+            None => return Err(String::from("the orb does not ask for co-signatures")),
+        };
+        let sig = signer.alg.sign_message(self.cosign_message(), key_pair);
+        self.add_cosignature(i, sig)?;
+        Ok(i)
+    }
+
+    // adds a co-signature made elsewhere over BackPack::cosign_message by the signer at place i in the policy.
+    pub fn add_cosignature(&mut self, i: usize, sig: Box<[u8]>) -> Result<(), String> 
+    where
+        U: Serialize,
+        V: Serialize,
+    {
+        let msg = self.cosign_message();
+        match self.cosign.as_mut() {
+            Some(cosign) => cosign.add(msg, i, sig),
+            None => Err(String::from("the orb does not ask for co-signatures")),
+        }
+    }
+
+    // whether the orb asks for exactly the co-signing policy given, as a verifier demanding one must check.
+    // the co-signatures themselves are checked by MarkZero::report.
+    pub fn requires(&self, policy: &Policy) -> Result<(), String> {
+        match self.cosign {
+            Some(ref cosign) if cosign.policy == *policy => Ok(()),
+            Some(_) => Err(String::from("the orb asks for a different co-signing policy")),
+            None => Err(String::from("the orb does not ask for co-signatures")),
+        }
+    }
+
+    // the number of field elements the Marker will check against the proof.
+    fn supplied(&self) -> Result<usize, String> {
+        let nums = self.ver.as_ref().map_or(0, Vec::len) 
+            + self.vs.as_ref().map_or(0, Vec::len) 
+            + self.vf.as_ref().map_or(0, Vec::len);
+        match (&self.vb, &self.ut) {
+            (Some(vec), Some(tag)) => match width(tag) {
+                Some(len) => Ok(nums + vec.len() * len),
+                None => Err(format!("unknown bit width tag {}", tag)),
+            },
+            (Some(_), None) => Err(String::from("public bits were supplied without a width tag")),
+            (None, _) => Ok(nums),
+        }
+    }
 }
 
 impl<'de, A, B, T, U, V, W> GoZero<'de> for Andromeda<A, B, T, U, V, W> 
@@ -131,22 +435,130 @@ where
 {  
     type Returner = BackPack<A, T, U, V, W>;
 
-    fn go(self) -> Self::Returner {
+    fn try_go(mut self) -> Result<Self::Returner, SignerError> {
         let prf = self.weights.new(self.crs.clone());
-        let sig = EdDSA::into(
-            serde_json::to_string(&prf)
-            .expect("Andromemda::GoZero::go() failed to parse &prf as a string for the EdDSA tuple-struct")   
-        ).sign_message(&self.key_pair);
-        BackPack::into(
+        let (iat, nonce, alg) = (replay::now(), replay::nonce(), self.signer.algorithm());
+        let sig = self.signer.sign(
+            payload(&prf, iat, &nonce, alg, self.policy.as_ref()).as_bytes()
+        )?;
+        Ok(BackPack::into(
             prf,
             self.compute_out,
+            self.compute_bits,
+            self.compute_signed,
+            self.compute_elements,
+            self.tag,
             sig,
-            EdDSA::<String>::public_key(&self.key_pair)
-                .as_ref()
-                .to_vec()
-                .into_boxed_slice(),
+            self.signer.public_key(),
             self.crs,
-        )
+            iat,
+            nonce,
+            alg,
+            self.policy.map(CoSign::into),
+            self.chain,
+        ))
+    }
+}
+
+impl<A, T, U, V, W> FromZeroRef for BackPack<A, T, U, V, W> 
+where
+    for <'de>
+    A: Common<T, U, V>
+        + Deserialize<'de>,
+    T: Field 
+        + From<usize> 
+        + FromStr,
+    for <'de>
+    U: Deserialize<'de>,
+    for <'de>
+    V: Deserialize<'de>,
+{
+    type C = Columns;
+
+    fn from_copy(c: Self::C) -> Result<Self, PartsError> {
+        Self::from_parts(c)
+    }
+
+    // lengths are checked against the named scheme so a truncated column fails here rather than as a bad signature.
+    fn from_parts<S: AsRef<[u8]>>(parts: Columns<S>) -> Result<Self, PartsError> {
+        fn parse<'a, X: Deserialize<'a>>(field: &'static str, bytes: &'a [u8]) -> Result<X, PartsError> {
+            serde_json::from_slice(bytes).map_err(|e| PartsError::Parse(field, e.to_string()))
+        }
+        let prf: Proof<U, V> = parse("prf", parts.prf.as_ref())?;
+        let ver: Option<Vec<usize>> = parse("ver", parts.ver.as_ref())?;
+        let vb: Option<Vec<usize>> = parse("vb", parts.vb.as_ref())?;
+        let vs: Option<Vec<isize>> = parse("vs", parts.vs.as_ref())?;
+        let vf: Option<Vec<String>> = parse("vf", parts.vf.as_ref())?;
+        let ut: Option<String> = parse("ut", parts.ut.as_ref())?;
+        let sig: Box<[u8]> = parse("sig", parts.sig.as_ref())?;
+        let puk: Box<[u8]> = parse("puk", parts.puk.as_ref())?;
+        let crs: A = parse("crs", parts.crs.as_ref())?;
+        let iat: u64 = parse("iat", parts.iat.as_ref())?;
+        let nonce: Box<[u8]> = parse("nonce", parts.nonce.as_ref())?;
+        let alg: Algorithm = parse("alg", parts.alg.as_ref())?;
+        let cosign: Option<CoSign> = parse("cosign", parts.cosign.as_ref())?;
+        let chain: Option<Vec<Certificate>> = parse("chain", parts.chain.as_ref())?;
+
+        if sig.len() != alg.signature_len() {
+            return Err(PartsError::Invalid("sig", format!("expected {} bytes for {}, found {}", alg.signature_len(), alg, sig.len())))
+        }
+        if puk.len() != alg.public_key_len() {
+            return Err(PartsError::Invalid("puk", format!("expected {} bytes for {}, found {}", alg.public_key_len(), alg, puk.len())))
+        }
+        if let Some(f) = vf.iter().flatten().find(|f| T::from_str(f).is_err()) {
+            return Err(PartsError::Invalid("vf", format!("{} is not a field element", f)))
+        }
+        if nonce.is_empty() {
+            return Err(PartsError::Invalid("nonce", String::from("the nonce is empty")))
+        }
+        if let Some(ref cosign) = cosign {
+            cosign.policy.check().map_err(|msg| PartsError::Invalid("cosign", msg))?;
+        }
+        let expected = inspect_crs(&crs).map_err(|msg| PartsError::Invalid("crs", msg))?;
+        let orb = BackPack::into(prf, ver, vb, vs, vf, ut, sig, puk, crs, iat, nonce, alg, cosign, chain);
+        match orb.supplied() {
+            Ok(supplied) if supplied == expected => Ok(orb),
+            Ok(supplied) => Err(PartsError::Invalid("ver", format!("expected {} public inputs, found {}", expected, supplied))),
+            Err(msg) => Err(PartsError::Invalid("ut", msg)),
+        }
+    }
+}
+
+// parses the code held in the crs and checks it agrees with the QAP, returning the number of public inputs.
+fn inspect_crs<A, T, U, V>(crs: &A) -> Result<usize, String> 
+where
+    A: Common<T, U, V>,
+    T: Field 
+        + From<usize> 
+        + FromStr,
+{
+    let (code, qap, _, _) = crs.clone().get();
+    match ASTParser::try_parse(&code) {
+        Ok(rep) => {
+            let rep: DummyRep<T> = rep;
+            match rep.u.len() == qap.u.len() && rep.input == qap.input {
+                true => Ok(qap.input),
+                false => Err(String::from("the QAP does not match the code it was derived from")),
+            }
+        },
+        Err(_) => Err(String::from("the code in the CommonReference does not parse")),
+    }
+}
+
+const COSIGN_TAG: &str = "zero_orb cosign\0";
+
+// the message signed by the prover: the proof bound to its issue time, nonce and signature scheme,
+// and to the co-signing policy when there is one. orbs without a policy sign what they always have.
+fn payload<U, V>(prf: &Proof<U, V>, iat: u64, nonce: &[u8], alg: Algorithm, policy: Option<&Policy>) -> String 
+where
+    U: Serialize,
+    V: Serialize,
+{
+    match policy {
+        Some(policy) => serde_json::to_string(&(prf, iat, nonce, alg, policy))
+            .expect("interface::payload() failed to parse (&prf, iat, &nonce, alg, &policy) as a string for signing"),
+        None => serde_json::to_string(&(prf, iat, nonce, alg))
+            .expect("interface::payload() failed to parse (&prf, iat, &nonce, alg) as a string for signing"),
     }
 }
 
@@ -159,6 +571,7 @@ where
         + From<usize> 
         + Copy 
         + EllipticEncryptable<G1 = U, G2 = V, GT = W>
+        + FromStr
         + Serialize
         + Deserialize<'de>,
     for <'de>
@@ -175,30 +588,138 @@ where
     W: Add<Output = W> 
         + PartialEq, 
 {
-    fn verify(self) -> bool {
-        use serde_json::to_string;
-        use crate::{
-            knowledge::{Marker, zkVerify},
-            crypto::{EdDSA, SignatureScheme},
+    // the signature is always checked, the proof only once the crs and public inputs are known to line up.
+    fn report(self) -> VerificationReport {
+        use crate::knowledge::{Marker, zkVerify};
+
+        let signature = match self.alg.verify_signature(self.message(), &self.sig, &self.puk) {
+            true => Check::Passed,
+            false => Check::Failed,
+        };
+        let (crs, inputs, mut detail) = match (inspect_crs(&self.crs), self.supplied()) {
+            (Ok(expected), Ok(supplied)) => match expected == supplied {
+                true => (Check::Passed, Check::Passed, None),
+                false => (
+                    Check::Passed, 
+                    Check::Failed, 
+                    Some(format!("expected {} public inputs, found {}", expected, supplied))
+                ),
+            },
+            (Ok(_), Err(msg)) => (Check::Passed, Check::Failed, Some(msg)),
+            (Err(msg), _) => (Check::Failed, Check::Skipped, Some(msg)),
         };
-        match (
-            EdDSA::into(
-                to_string(&self.prf).unwrap()
-            ).verify_signature(
-                &self.sig,
-                &self.puk
-            ),
-            Marker::into(
+        let (approval, unapproved) = match self.cosign {
+            Some(ref cosign) => match cosign.check(self.cosign_message()) {
+                Ok(()) => (Check::Passed, None),
+                Err(msg) => (Check::Failed, Some(msg)),
+            },
+            None => (Check::Skipped, None),
+        };
+        let proof = match (crs, inputs) {
+            (Check::Passed, Check::Passed) => match Marker::into(
                 self.ver,
-                None,
-                None,
-            ).check(
+                self.vb,
+                self.ut,
+            ).with_signed(self.vs).with_elements(self.vf).check(
                 self.crs,
                 self.prf,
-            ),
-        ) {
-            (true, true) => true,
-            (_, _) => false
+            ) {
+                true => Check::Passed,
+                false => Check::Failed,
+            },
+            (_, _) => Check::Skipped,
+        };
+        if let (None, Check::Failed) = (&detail, signature) {
+            detail = Some(String::from("the signature does not match the public key"));
+        }
+        if let None = detail {
+            detail = unapproved;
+        }
+        if let (None, Check::Failed) = (&detail, proof) {
+            detail = Some(String::from("the proof does not verify against the public inputs"));
+        }
+        VerificationReport::into(crs, inputs, signature, approval, proof, detail)
+    }
+}
+
+impl<A, T, U, V, W> MarkFresh for BackPack<A, T, U, V, W> 
+where
+    BackPack<A, T, U, V, W>: MarkZero,
+{
+    fn report_fresh<S: NonceStore>(self, store: &mut S, window: &Window, now: u64) -> VerificationReport {
+        use crate::report::Reason;
+
+        if let Err(rejection) = window.check(self.iat, now) {
+            return VerificationReport::rejected(
+                rejection, 
+                Some(format!("issued at {}, checked at {}", self.iat, now))
+            )
+        }
+        let (iat, nonce) = (self.iat, self.nonce.clone());
+        let mut report = self.report();
+        if report.is_valid() {
+            // nonces from before the window opened are forgotten first, so the store never outgrows the window.
+            let inserted = store.prune(window.opens(now)).and_then(|_| store.insert(&nonce, iat));
+            match inserted {
+                Ok(true) => report.freshness = Check::Passed,
+                Ok(false) => {
+                    report.freshness = Check::Failed;
+                    report.reason = Reason::Replayed;
+                    report.detail = Some(String::from("the nonce has already been seen"));
+                },
+                Err(e) => {
+                    report.freshness = Check::Failed;
+                    report.reason = Reason::StoreFailed;
+                    report.detail = Some(format!("the nonce could not be recorded: {}", e));
+                },
+            }
+        }
+        report
+    }
+}
+
+impl<A, T, U, V, W> MarkApproved for BackPack<A, T, U, V, W> 
+where
+    BackPack<A, T, U, V, W>: MarkZero,
+{
+    fn report_approved(self, policy: &Policy) -> VerificationReport {
+        match self.requires(policy) {
+            Ok(()) => self.report(),
+            Err(msg) => VerificationReport::unapproved(Some(msg)),
+        }
+    }
+}
+
+impl<A, T, U, V, W> MarkTrusted for BackPack<A, T, U, V, W> 
+where
+    BackPack<A, T, U, V, W>: MarkZero + MarkFresh,
+{
+    fn report_trusted(self, policy: &TrustPolicy, window: &Window, now: u64) -> VerificationReport {
+        if let Err(rejection) = window.check(self.iat, now) {
+            return VerificationReport::rejected(
+                rejection, 
+                Some(format!("issued at {}, checked at {}", self.iat, now))
+            )
+        }
+        match policy.check_certified(self.alg, &self.puk, self.iat, now, self.chain.as_ref().map(Vec::as_slice)) {
+            Ok(subject) => {
+                let mut report = self.report();
+                report.key = Check::Passed;
+                report.subject = subject;
+                report
+            },
+            Err(distrust) => VerificationReport::distrusted(distrust.reason(), Some(distrust.to_string())),
+        }
+    }
+    fn report_trusted_fresh<S: NonceStore>(self, policy: &TrustPolicy, store: &mut S, window: &Window, now: u64) -> VerificationReport {
+        match policy.check_certified(self.alg, &self.puk, self.iat, now, self.chain.as_ref().map(Vec::as_slice)) {
+            Ok(subject) => {
+                let mut report = self.report_fresh(store, window, now);
+                report.key = Check::Passed;
+                report.subject = subject;
+                report
+            },
+            Err(distrust) => VerificationReport::distrusted(distrust.reason(), Some(distrust.to_string())),
         }
     }
 }
@@ -209,21 +730,39 @@ where
     U: Serialize, 
     V: Serialize,
 {
-    type C = (String, String, String, String, String);
+    type C = Columns;
     
     fn copy_str(&self) -> Self::C {
-        return(
-            serde_json::to_string(&self.prf)
+        Columns {
+            prf: serde_json::to_string(&self.prf)
                 .expect("BackPack::ZeroRef::copy::to_string::prf panicked whilst deserializing field prf for BackPack"),
-            serde_json::to_string(&self.ver)
+            ver: serde_json::to_string(&self.ver)
                 .expect("BackPack::ZeroRef::copy::to_string::ver panicked whilst deserializing field ver for BackPack"),
-            serde_json::to_string(&self.sig)
+            vb: serde_json::to_string(&self.vb)
+                .expect("BackPack::ZeroRef::copy::to_string::vb panicked whilst deserializing field vb for BackPack"),
+            vs: serde_json::to_string(&self.vs)
+                .expect("BackPack::ZeroRef::copy::to_string::vs panicked whilst deserializing field vs for BackPack"),
+            vf: serde_json::to_string(&self.vf)
+                .expect("BackPack::ZeroRef::copy::to_string::vf panicked whilst deserializing field vf for BackPack"),
+            ut: serde_json::to_string(&self.ut)
+                .expect("BackPack::ZeroRef::copy::to_string::ut panicked whilst deserializing field ut for BackPack"),
+            sig: serde_json::to_string(&self.sig)
                 .expect("BackPack::ZeroRef::copy::to_string::sig panicked whilst deserializing field sig for BackPack"),
-            serde_json::to_string(&self.puk)
+            puk: serde_json::to_string(&self.puk)
                 .expect("BackPack::ZeroRef::copy::to_string::puk panicked whilst deserializing field puk for BackPack"),
-            serde_json::to_string(&self.crs)
+            crs: serde_json::to_string(&self.crs)
                 .expect("BackPack::ZeroRef::copy::to_string::crs panicked whilst deserializing field crs for BackPack"),
-        )
+            iat: serde_json::to_string(&self.iat)
+                .expect("BackPack::ZeroRef::copy::to_string::iat panicked whilst deserializing field iat for BackPack"),
+            nonce: serde_json::to_string(&self.nonce)
+                .expect("BackPack::ZeroRef::copy::to_string::nonce panicked whilst deserializing field nonce for BackPack"),
+            alg: serde_json::to_string(&self.alg)
+                .expect("BackPack::ZeroRef::copy::to_string::alg panicked whilst deserializing field alg for BackPack"),
+            cosign: serde_json::to_string(&self.cosign)
+                .expect("BackPack::ZeroRef::copy::to_string::cosign panicked whilst deserializing field cosign for BackPack"),
+            chain: serde_json::to_string(&self.chain)
+                .expect("BackPack::ZeroRef::copy::to_string::chain panicked whilst deserializing field chain for BackPack"),
+        }
     }
     fn get_prf_str(&self) -> String {
         serde_json::to_string(&self.prf).expect("BackPack::ZeroRef::get_prf_str::to_string::prf panicked whilst deserializing field prf for BackPack")
@@ -231,6 +770,18 @@ where
     fn get_ver_str(&self) -> String {
         serde_json::to_string(&self.ver).expect("BackPack::ZeroRef::get_ver_str::to_string::ver panicked whilst deserializing field ver for BackPack")
     }
+    fn get_vb_str(&self) -> String {
+        serde_json::to_string(&self.vb).expect("BackPack::ZeroRef::get_vb_str::to_string::vb panicked whilst deserializing field vb for BackPack")
+    }
+    fn get_vs_str(&self) -> String {
+        serde_json::to_string(&self.vs).expect("BackPack::ZeroRef::get_vs_str::to_string::vs panicked whilst deserializing field vs for BackPack")
+    }
+    fn get_vf_str(&self) -> String {
+        serde_json::to_string(&self.vf).expect("BackPack::ZeroRef::get_vf_str::to_string::vf panicked whilst deserializing field vf for BackPack")
+    }
+    fn get_ut_str(&self) -> String {
+        serde_json::to_string(&self.ut).expect("BackPack::ZeroRef::get_ut_str::to_string::ut panicked whilst deserializing field ut for BackPack")
+    }
     fn get_sig_str(&self) -> String {
         serde_json::to_string(&self.sig).expect("BackPack::ZeroRef::get_sig_str::to_string::sig panicked whilst deserializing field sig for BackPack")
     }
@@ -240,26 +791,54 @@ where
     fn get_crs_str(&self) -> String {
         serde_json::to_string(&self.crs).expect("BackPack::ZeroRef::get_crs_str::to_string::crs panicked whilst deserializing field crs for BackPack")
     }
+    fn get_iat_str(&self) -> String {
+        serde_json::to_string(&self.iat).expect("BackPack::ZeroRef::get_iat_str::to_string::iat panicked whilst deserializing field iat for BackPack")
+    }
+    fn get_nonce_str(&self) -> String {
+        serde_json::to_string(&self.nonce).expect("BackPack::ZeroRef::get_nonce_str::to_string::nonce panicked whilst deserializing field nonce for BackPack")
+    }
+    fn get_alg_str(&self) -> String {
+        serde_json::to_string(&self.alg).expect("BackPack::ZeroRef::get_alg_str::to_string::alg panicked whilst deserializing field alg for BackPack")
+    }
+    fn get_cosign_str(&self) -> String {
+        serde_json::to_string(&self.cosign).expect("BackPack::ZeroRef::get_cosign_str::to_string::cosign panicked whilst deserializing field cosign for BackPack")
+    }
+    fn get_chain_str(&self) -> String {
+        serde_json::to_string(&self.chain).expect("BackPack::ZeroRef::get_chain_str::to_string::chain panicked whilst deserializing field chain for BackPack")
+    }
 }
 
 #[cfg(test)]
 mod test {
     use zksnark::groth16::fr::{
-        FrLocal, G1Local, G2Local,
+        FrLocal, G1Local, G2Local, GtLocal,
+    };
+    use std::{
+        collections::BTreeMap,
+        io::BufReader,
+        os::unix::net::UnixStream,
+        thread,
     };
-    use std::fs::read_to_string;
     use crate::{
         common::{CommonReference, Common},
-        crypto::{EdDSA, SignatureScheme},
+        crypto::{
+            EdDSA, SignatureScheme, Algorithm, 
+            cert::Certificate,
+            keystore::KeyHandle,
+            signer::{self, Callback, PipeSigner, SignerError},
+        },
+        cosign::{Policy, Signer},
         knowledge::Knowledge,
-        interface::{GoZero, MarkZero, Andromeda},
+        interface::{GoZero, MarkZero, MarkFresh, MarkTrusted, MarkApproved, ZeroRef, FromZeroRef, Columns, PartsError, Andromeda, BackPack},
+        replay::{self, NonceStore, MemoryStore, Window, Rejection},
+        report::{Check, Reason},
+        tests::{sample_crs, sample_orb, sample_prover},
+        trust::{TrustPolicy, TrustedKey, Revocation, Rotation},
     };
 
     #[test]
     fn test_andromeda_parsing() {
-        let crs: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::read(
-            &read_to_string("src/tests/files/crs/sample.crs").unwrap()
-        );
+        let crs = sample_crs();
         let weights = |a, b: usize| -> Knowledge {
             Knowledge::into(
                 None, 
@@ -305,5 +884,404 @@ mod test {
                 EdDSA::<String>::init_key_pair()
             ).go().verify()
         );
+        let key = KeyHandle::generate("prover", Algorithm::Ed25519);
+        let orb = Andromeda::into(
+            crs.clone(), 
+            weights(20, 5), 
+            Some(vec![100]),
+            key.clone()
+        ).go();
+        assert_eq!(key.public_key(), orb.puk);
+        assert_eq!(true, orb.verify());
+    }
+
+    #[test]
+    fn test_replay_protection() {
+        let crs = sample_crs();
+        let orb = || sample_orb(&crs, 100, EdDSA::<String>::init_key_pair());
+        let (mut store, window) = (MemoryStore::new(), Window::default());
+
+        let first = orb();
+        let copy: String = serde_json::to_string(&first).unwrap();
+        assert_eq!(Ok(()), first.verify_fresh(&mut store, &window, replay::now()));
+        // the very same orb presented a second time.
+        let replayed: BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal> = serde_json::from_str(&copy).unwrap();
+        assert_eq!(Err(Rejection::Replayed), replayed.verify_fresh(&mut store, &window, replay::now()));
+        // an orb presented after its window has closed.
+        assert_eq!(
+            Err(Rejection::Stale), 
+            orb().verify_fresh(&mut store, &window, replay::now() + window.max_age + 1)
+        );
+        // moving the issue time breaks the signature.
+        let mut moved = orb();
+        moved.iat -= 1;
+        assert_eq!(Err(Rejection::Invalid), moved.verify_fresh(&mut store, &window, replay::now()));
+
+        // a store that cannot record the nonce turns the orb away rather than let it be replayed later.
+        struct Full;
+        impl NonceStore for Full {
+            fn insert(&mut self, _: &[u8], _: u64) -> std::io::Result<bool> {
+                Err(std::io::Error::new(std::io::ErrorKind::Other, "no space left on device"))
+            }
+            fn prune(&mut self, _: u64) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        let report = orb().report_fresh(&mut Full, &window, replay::now());
+        assert_eq!((Reason::StoreFailed, Check::Failed), (report.reason, report.freshness));
+        assert_eq!(Err(Rejection::Unavailable), orb().verify_fresh(&mut Full, &window, replay::now()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_verification_report() {
+        let crs = sample_crs();
+        let orb = |out: usize| sample_orb(&crs, out, EdDSA::<String>::init_key_pair());
+
+        let report = orb(100).report();
+        assert_eq!(Reason::Valid, report.reason);
+        assert_eq!(
+            (Check::Passed, Check::Passed, Check::Passed, Check::Passed), 
+            (report.crs, report.inputs, report.signature, report.proof)
+        );
+        assert_eq!(None, report.detail);
+
+        let report = orb(90).report();
+        assert_eq!(Reason::BadProof, report.reason);
+        assert_eq!((Check::Passed, Check::Failed), (report.signature, report.proof));
+
+        let mut tampered = orb(100);
+        tampered.puk = EdDSA::<String>::public_key(&EdDSA::<String>::init_key_pair());
+        let report = tampered.report();
+        assert_eq!(Reason::BadSignature, report.reason);
+        assert_eq!((Check::Failed, Check::Passed), (report.signature, report.proof));
+
+        let mut padded = orb(100);
+        padded.ver = Some(vec![100, 100]);
+        let report = padded.report();
+        assert_eq!(Reason::PublicInputMismatch, report.reason);
+        assert_eq!(Check::Skipped, report.proof);
+        
+        let mut swapped = orb(100);
+        swapped.crs.code = String::from("(in a b c) (out x) (verify x) (program (= x (* a b)))");
+        let report = swapped.report();
+        assert_eq!(Reason::MalformedCrs, report.reason);
+        assert_eq!((Check::Failed, Check::Skipped), (report.crs, report.proof));
+
+        let report = orb(100).report_fresh(&mut MemoryStore::new(), &Window::default(), 0);
+        assert_eq!(Reason::Premature, report.reason);
+        assert_eq!(Check::Failed, report.freshness);
+    }
+
+    #[test]
+    fn test_from_parts() {
+        type Orb = BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal>;
+
+        let crs = sample_crs();
+        let orb = sample_orb(&crs, 100, EdDSA::<String>::init_key_pair());
+
+        // round trip through the strings handed out by ZeroRef.
+        assert!(Orb::from_copy(orb.copy_str()).unwrap().verify());
+        // and through the same columns held as bytes.
+        let columns = orb.copy_str();
+        assert!(Orb::from_parts(columns.as_bytes()).unwrap().verify());
+
+        match Orb::from_parts(Columns { prf: "{}".as_bytes(), ..columns.as_bytes() }) {
+            Err(PartsError::Parse("prf", _)) => {},
+            _ => panic!("from_parts accepted a malformed proof"),
+        }
+        match Orb::from_parts(Columns { sig: "[1,2,3]".as_bytes(), ..columns.as_bytes() }) {
+            Err(PartsError::Invalid("sig", _)) => {},
+            _ => panic!("from_parts accepted a truncated signature"),
+        }
+        match Orb::from_parts(Columns { ver: "[100,100]".as_bytes(), ..columns.as_bytes() }) {
+            Err(PartsError::Invalid("ver", _)) => {},
+            _ => panic!("from_parts accepted the wrong number of public inputs"),
+        }
+        // an Ed25519 signature and key are the wrong lengths for P-384.
+        match Orb::from_parts(Columns { alg: "\"EcdsaP384\"".as_bytes(), ..columns.as_bytes() }) {
+            Err(PartsError::Invalid("sig", _)) => {},
+            _ => panic!("from_parts accepted a signature of the wrong scheme"),
+        }
+        let unmeetable = "{\"policy\":{\"threshold\":1,\"signers\":[]},\"sigs\":[]}";
+        match Orb::from_parts(Columns { cosign: unmeetable.as_bytes(), ..columns.as_bytes() }) {
+            Err(PartsError::Invalid("cosign", _)) => {},
+            _ => panic!("from_parts accepted a policy that cannot be met"),
+        }
+    }
+
+    #[test]
+    fn test_signature_schemes() {
+        let crs = sample_crs();
+        let orb = |x: usize, key_pair: Box<[u8]>| sample_orb(&crs, x, key_pair);
+        for alg in vec![Algorithm::Ed25519, Algorithm::EcdsaP256, Algorithm::EcdsaP384] {
+            let key_pair = alg.init_key_pair();
+            let good = orb(100, key_pair.clone());
+            assert_eq!(alg, good.alg);
+            assert_eq!(alg.public_key(&key_pair), good.puk);
+            assert_eq!(Reason::Valid, good.report().reason);
+            assert_eq!(Reason::BadProof, orb(90, key_pair.clone()).report().reason);
+
+            // relabelling the scheme breaks the signature, as the scheme is part of what was signed.
+            let mut relabelled = orb(100, key_pair);
+            relabelled.alg = match alg {
+                Algorithm::EcdsaP256 => Algorithm::EcdsaP384,
+                _ => Algorithm::EcdsaP256,
+            };
+            assert_eq!(Reason::BadSignature, relabelled.report().reason);
+        }
+        // orbs serialised before the scheme was recorded are refused rather than guessed at.
+        let mut json: serde_json::Value = serde_json::from_str(
+            &serde_json::to_string(&orb(100, Algorithm::Ed25519.init_key_pair())).unwrap()
+        ).unwrap();
+        json.as_object_mut().unwrap().remove("alg");
+        let legacy: Result<BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal>, _> = serde_json::from_value(json);
+        assert!(legacy.is_err());
+    }
+
+    #[test]
+    fn test_trusted_keys() {
+        let crs = sample_crs();
+        let key_pair = EdDSA::<String>::init_key_pair();
+        let puk = EdDSA::<String>::public_key(&key_pair);
+        let orb = || sample_orb(&crs, 100, key_pair.clone());
+        let mut policy = TrustPolicy {
+            allowed: vec![TrustedKey { alg: Algorithm::Ed25519, puk: puk.clone(), name: Some(String::from("clinic")) }],
+            revoked: vec![],
+            rotations: vec![],
+            roots: vec![],
+        };
+
+        let report = orb().report_trusted(&policy, &Window::default(), replay::now());
+        assert_eq!((Reason::Valid, Check::Passed), (report.reason, report.key));
+        let report = orb().report_trusted_fresh(&policy, &mut MemoryStore::new(), &Window::default(), replay::now());
+        assert_eq!((Reason::Valid, Check::Passed, Check::Passed), (report.reason, report.key, report.freshness));
+
+        let report = orb().report_trusted(&TrustPolicy::default(), &Window::default(), replay::now());
+        assert_eq!((Reason::UnknownKey, Check::Failed, Check::Skipped), (report.reason, report.key, report.signature));
+
+        let successor = EdDSA::<String>::public_key(&EdDSA::<String>::init_key_pair());
+        policy.rotations.push(Rotation { from: puk.clone(), to: TrustedKey { alg: Algorithm::Ed25519, puk: successor, name: None }, at: 1 });
+        assert_eq!(Reason::RotatedKey, orb().report_trusted(&policy, &Window::default(), replay::now()).reason);
+
+        policy.rotations.clear();
+        policy.revoked.push(Revocation { puk: puk.clone(), since: 0, reason: Some(String::from("compromised")) });
+        let report = orb().report_trusted(&policy, &Window::default(), replay::now());
+        assert_eq!(Reason::RevokedKey, report.reason);
+        assert_eq!(Some(String::from("the signing key was revoked at 0: compromised")), report.detail);
+        assert!(!orb().verify_trusted(&policy, &Window::default(), replay::now()));
+
+        // a key revoked a minute ago cannot slip its orbs past by backdating them to before the revocation.
+        let now = replay::now();
+        policy.revoked = vec![Revocation { puk: puk.clone(), since: now - 60, reason: None }];
+        let backdated = || {
+            let mut orb = orb();
+            orb.iat = now - 3600;
+            orb.sig = Algorithm::Ed25519.sign_message(orb.message(), &key_pair);
+            orb
+        };
+        assert_eq!(Reason::Valid, backdated().report().reason);
+        let report = backdated().report_trusted(&policy, &Window::default(), now);
+        assert_eq!((Reason::Stale, Check::Skipped), (report.reason, report.key));
+        assert_eq!(Reason::RevokedKey, orb().report_trusted(&policy, &Window::default(), now).reason);
+    }
+
+    #[test]
+    fn test_certified_orb() {
+        let crs = sample_crs();
+        let (mut root_key, mut clinic_key, key) = (
+            KeyHandle::generate("root", Algorithm::EcdsaP384),
+            KeyHandle::generate("clinic", Algorithm::EcdsaP256),
+            KeyHandle::generate("dr who", Algorithm::Ed25519),
+        );
+        let now = replay::now();
+        let cert = |subject: &str, key: &KeyHandle, ca: bool| Certificate::into(
+            subject, key.algorithm(), key.public_key(), BTreeMap::new(), now - 60, now + 600, ca
+        );
+        let root = cert("root", &root_key, true).sign("root", &mut root_key.clone()).unwrap();
+        let clinic = cert("clinic", &clinic_key, true).sign("root", &mut root_key).unwrap();
+        let leaf = cert("dr who", &key, false).sign("clinic", &mut clinic_key).unwrap();
+        let orb = |chain: Vec<Certificate>| sample_prover(&crs, 100, key.clone()).certified(chain).go();
+        let mut policy = TrustPolicy {
+            allowed: vec![],
+            revoked: vec![],
+            rotations: vec![],
+            roots: vec![root],
+        };
+
+        let report = orb(vec![leaf.clone(), clinic.clone()]).report_trusted(&policy, &Window::default(), replay::now());
+        assert_eq!((Reason::Valid, Check::Passed), (report.reason, report.key));
+        assert_eq!(Some(String::from("dr who")), report.subject);
+        // without the intermediate the chain does not reach the root.
+        assert_eq!(Reason::BadCertificate, orb(vec![leaf.clone()]).report_trusted(&policy, &Window::default(), replay::now()).reason);
+        // a chain for someone else's key proves nothing about this one.
+        let other = cert("mallory", &KeyHandle::generate("mallory", Algorithm::Ed25519), false).sign("clinic", &mut clinic_key).unwrap();
+        assert_eq!(Reason::BadCertificate, orb(vec![other, clinic.clone()]).report_trusted(&policy, &Window::default(), replay::now()).reason);
+        let mut renamed = leaf.clone();
+        renamed.subject = String::from("dr no");
+        assert_eq!(Reason::BadCertificate, orb(vec![renamed, clinic.clone()]).report_trusted(&policy, &Window::default(), replay::now()).reason);
+
+        // the chain is checked at the verifier's time, not at the issue time the prover chose.
+        let late = orb(vec![leaf.clone(), clinic.clone()]);
+        assert_eq!(Reason::BadCertificate, late.report_trusted(&policy, &Window::into(3600, 30), now + 700).reason);
+
+        policy.revoked.push(Revocation { puk: clinic.puk.clone(), since: 0, reason: None });
+        assert_eq!(Reason::RevokedKey, orb(vec![leaf.clone(), clinic.clone()]).report_trusted(&policy, &Window::default(), replay::now()).reason);
+        // and the chain survives the database columns.
+        let orb = orb(vec![leaf, clinic]);
+        let copy: BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal> = 
+            FromZeroRef::from_parts(orb.copy_str()).unwrap();
+        assert_eq!(orb.chain, copy.chain);
+    }
+
+    #[test]
+    fn test_external_signer() {
+        let crs = sample_crs();
+        let key = KeyHandle::generate("daemon", Algorithm::EcdsaP384);
+        let puk = key.public_key();
+        let (ours, theirs) = UnixStream::pair().unwrap();
+        let daemon = thread::spawn(move || {
+            let mut key = key;
+            signer::serve(&mut key, BufReader::new(theirs.try_clone().unwrap()), theirs).unwrap();
+        });
+        let orb = Andromeda::into_signer(
+            crs.clone(), 
+            Knowledge::into_num_only(Some(vec![20, 5]), None), 
+            Some(vec![100]),
+            None,
+            None,
+            PipeSigner::into(BufReader::new(ours.try_clone().unwrap()), ours).unwrap()
+        ).go();
+        daemon.join().unwrap();
+        assert_eq!((Algorithm::EcdsaP384, puk.clone()), (orb.alg, orb.puk.clone()));
+        assert!(orb.verify());
+
+        // a failing signer surfaces through try_go rather than as an unsigned orb.
+        let refused = Andromeda::into_signer(
+            crs.clone(), 
+            Knowledge::into_num_only(Some(vec![20, 5]), None), 
+            Some(vec![100]),
+            None,
+            None,
+            Callback::into(Algorithm::EcdsaP384, puk, |_: &[u8]| Err(SignerError::Refused(String::from("locked"))))
+        ).try_go();
+        match refused {
+            Err(SignerError::Refused(_)) => (),
+            _ => panic!("an orb was produced without a signature"),
+        }
+    }
+
+    #[test]
+    fn test_cosigned_orb() {
+        type Orb = BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal>;
+
+        let crs = sample_crs();
+        let (clinician, auditor, registrar) = (
+            Algorithm::Ed25519.init_key_pair(),
+            Algorithm::EcdsaP256.init_key_pair(),
+            Algorithm::EcdsaP384.init_key_pair(),
+        );
+        let policy = Policy::into(2, vec![
+            Signer::of_key(&clinician).unwrap(),
+            Signer::of_key(&auditor).unwrap(),
+            Signer::of_key(&registrar).unwrap(),
+        ]).unwrap();
+        let orb = |policy: Option<Policy>| {
+            let andromeda = sample_prover(&crs, 100, EdDSA::<String>::init_key_pair());
+            match policy {
+                Some(policy) => andromeda.cosigned(policy).go(),
+                None => andromeda.go(),
+            }
+        };
+
+        let check = |orb: &Orb| Orb::from_copy(orb.copy_str()).unwrap().report();
+
+        assert_eq!(Check::Skipped, orb(None).report().approval);
+        let mut cosigned = orb(Some(policy.clone()));
+        let report = check(&cosigned);
+        assert_eq!((Reason::Unapproved, Check::Failed), (report.reason, report.approval));
+
+        assert_eq!(Ok(0), cosigned.cosign(&clinician));
+        assert!(cosigned.cosign(&Algorithm::Ed25519.init_key_pair()).is_err());
+        let report = check(&cosigned);
+        assert_eq!(Reason::Unapproved, report.reason);
+        assert_eq!(Some(String::from("1 of the 2 required co-signatures verify")), report.detail);
+        // the auditor signs the message elsewhere and hands back the signature.
+        let sig = Algorithm::EcdsaP256.sign_message(cosigned.cosign_message(), &auditor);
+        assert!(cosigned.add_cosignature(2, sig.clone()).is_err());
+        cosigned.add_cosignature(1, sig).unwrap();
+        let report = check(&cosigned);
+        assert_eq!((Reason::Valid, Check::Passed), (report.reason, report.approval));
+
+        // the policy is covered by the prover's signature, so it can neither be dropped nor loosened.
+        let mut dropped = Orb::from_copy(cosigned.copy_str()).unwrap();
+        dropped.cosign = None;
+        assert_eq!(Reason::BadSignature, dropped.report().reason);
+        let mut loosened = Orb::from_copy(cosigned.copy_str()).unwrap();
+        loosened.cosign.as_mut().unwrap().policy.threshold = 1;
+        assert_eq!(Reason::BadSignature, loosened.report().reason);
+        assert!(orb(None).cosign(&clinician).is_err());
+
+        // a verifier demanding the 2 of 3 policy turns away orbs that ask for less, however well co-signed.
+        assert_eq!(Reason::Valid, check(&cosigned).reason);
+        assert_eq!(Reason::Valid, Orb::from_copy(cosigned.copy_str()).unwrap().report_approved(&policy).reason);
+        let report = orb(None).report_approved(&policy);
+        assert_eq!((Reason::Unapproved, Check::Failed, Check::Skipped), (report.reason, report.approval, report.signature));
+        let mut lax = orb(Some(Policy::into(1, policy.signers.clone()).unwrap()));
+        lax.cosign(&clinician).unwrap();
+        assert_eq!(Reason::Valid, Orb::from_copy(lax.copy_str()).unwrap().report().reason);
+        assert_eq!(Reason::Unapproved, lax.report_approved(&policy).reason);
+
+        // a prover named in the policy cannot pass its own signature off as a co-signature.
+        let mut own = sample_prover(&crs, 100, clinician.clone())
+            .cosigned(Policy::into(1, policy.signers.clone()).unwrap())
+            .go();
+        let sig = own.sig.clone();
+        assert_eq!(Err(String::from("the co-signature does not match signer 0")), own.add_cosignature(0, sig.clone()));
+        own.cosign.as_mut().unwrap().sigs.push((0, sig));
+        let report = check(&own);
+        assert_eq!((Reason::Unapproved, Check::Failed), (report.reason, report.approval));
+        assert_eq!(Ok(0), own.cosign(&clinician));
+        assert_eq!(Reason::Valid, check(&own).reason);
+    }
+
+    #[test]
+    fn test_public_bits() {
+        // x = sum(a_i * b_i) over a private u8 a and a public u8 b.
+        // like the comparators, verify lists the numeric output ahead of the public bits.
+        let code = format!(
+            "(in {a} {b}) (out x) (verify x {b}) (program {t} (= x (* 1 (+ {s}))))",
+            a = (0..8).map(|i| format!("a{}", i)).collect::<Vec<_>>().join(" "),
+            b = (0..8).map(|i| format!("b{}", i)).collect::<Vec<_>>().join(" "),
+            t = (0..8).map(|i| format!("(= t{} (* a{} b{}))", i, i, i)).collect::<Vec<_>>().join(" "),
+            s = (0..8).map(|i| format!("t{}", i)).collect::<Vec<_>>().join(" "),
+        );
+        let crs: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::new(code);
+        let orb = |a: usize, b: usize, x: usize| Andromeda::into_bits(
+            crs.clone(), 
+            Knowledge::into(
+                Some(vec![a]), 
+                Some(vec![b]), 
+                None, 
+                None, 
+                Some(String::from("u8"))
+            ), 
+            Some(vec![x]),
+            Some(vec![b]),
+            Some(String::from("u8")),
+            EdDSA::<String>::init_key_pair()
+        ).go();
+        
+        assert!(orb(0b1011, 0b0110, 1).verify());
+        assert!(orb(0b1111, 0b0111, 3).verify());
+        assert!(!orb(0b1011, 0b0110, 2).verify());
+
+        // the public bits are part of the statement, so changing them breaks the proof.
+        let mut moved = orb(0b1011, 0b0110, 1);
+        moved.vb = Some(vec![0b0111]);
+        assert!(!moved.verify());
+        
+        let mut untagged = orb(0b1011, 0b0110, 1);
+        untagged.ut = None;
+        assert_eq!(Reason::PublicInputMismatch, untagged.report().reason);
+    }
+}
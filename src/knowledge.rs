@@ -1,6 +1,6 @@
 use crate::{
     common::Common,
-    transform::into_field::IntoField,
+    transform::into_field::{self, IntoField},
 };
 use zksnark::{
     Proof, CoefficientPoly,
@@ -17,6 +17,7 @@ use std::{
     iter::Sum,
 };
 use serde_derive::{Serialize, Deserialize};
+use num::BigUint;
 
 pub trait zkProof {
     fn new<C, T, U, V>(self, crs: C) -> Proof<U, V> 
@@ -40,12 +41,16 @@ pub trait zkVerify {
         T: Field 
             + Copy 
             + From<usize>
+            + FromStr
             + EllipticEncryptable<G1 = U, G2 = V, GT = W>,
         U: Sum,
         V: Add<Output=V> + Sum + Copy,
         W: Add<Output = W> + PartialEq, ;
 }
 
+// ws and vs hold private and public signed nums, assigned after the other nums as IntoField gives a Vec<isize>.
+// wh holds private hints, field elements in decimal such as an inverse, that no usize can carry.
+// they are assigned last, in the order the circuit declares them with CircuitBuilder::hint.
 #[derive(Serialize, Deserialize)]
 pub struct Knowledge {
     wb: Option<Vec<usize>>,
@@ -53,6 +58,12 @@ pub struct Knowledge {
     wn: Option<Vec<usize>>,
     vn: Option<Vec<usize>>,
     ut: Option<String>,
+    #[serde(default)]
+    ws: Option<Vec<isize>>,
+    #[serde(default)]
+    vs: Option<Vec<isize>>,
+    #[serde(default)]
+    wh: Option<Vec<String>>,
 }
 
 impl zkProof for Knowledge {
@@ -69,35 +80,9 @@ impl zkProof for Knowledge {
         U: Add<Output=U> + Sub<Output=U> + Sum + Copy,
         V: Add<Output=V> + Sum + Copy,
     {    
-        let mut assignments: Vec<T> = Vec::new();
-        match self.wb {
-            Some(vec) => {
-                match self.ut.clone() {
-                    Some(tag) => assignments.append(&mut vec.collect_bits(&tag)),
-                    None => {},
-                };
-            },
-            None => {}, 
-        }
-        match self.vb {
-            Some(vec) => {
-                match self.ut {
-                    Some(tag) => assignments.append(&mut vec.collect_bits(&tag)),
-                    None => {},
-                };
-            },
-            None => {}, 
-        }
-        match self.wn {
-            Some(vec) => assignments.append(&mut vec.collect_nums()),
-            None => {}, 
-        }
-        match self.vn {
-            Some(vec) => assignments.append(&mut vec.collect_nums()),
-            None => {}, 
-        }
+        let assignments = self.assignments().expect("Knowledge::new() assigning the witness");
         let (code, qap, sg1, sg2) = crs.get();
-        let weights = groth16::weights(&code, &assignments).expect("groth16::weights");    
+        let weights = groth16::weights(&code, &assignments).expect("groth16::weights");
         groth16::prove(
             &qap,
             (&sg1, &sg2),
@@ -120,6 +105,9 @@ impl Knowledge {
             wn: wn,
             vn: vn,
             ut: ut,
+            ws: None,
+            vs: None,
+            wh: None,
         }
     }
     pub fn into_num_only(wn: Option<Vec<usize>>, vn: Option<Vec<usize>>) -> Self {
@@ -127,12 +115,118 @@ impl Knowledge {
             None, None, wn, vn, None
         )
     }
+    // each number handed over twice, as its bits under ut and as itself, for circuits that declare them with
+    // CircuitBuilder::witness_number and input_number. the circuit checks the two agree.
+    pub fn into_linked(wn: Option<Vec<usize>>, vn: Option<Vec<usize>>, ut: String) -> Self {
+        Self::into(
+            wn.clone(), vn.clone(), wn, vn, Some(ut)
+        )
+    }
+    // each signed number handed over twice, as its two's complement bits under ut and as itself, for circuits that
+    // declare them with CircuitBuilder::witness_signed and input_signed. the circuit checks the two agree.
+    pub fn into_signed(ws: Option<Vec<isize>>, vs: Option<Vec<isize>>, ut: String) -> Self {
+        let bits = |v: &Option<Vec<isize>>| v.as_ref().map(|v| v.iter()
+            .map(|&x| into_field::twos_complement(x, &ut)
+                .expect("Knowledge::into_signed() a signed value out of range for its tag"))
+            .collect());
+        Self::into(
+            bits(&ws), bits(&vs), None, None, Some(ut.clone())
+        ).with_signed(ws, vs)
+    }
+    // the signed nums, for circuits that declare them alongside bits given some other way.
+    pub fn with_signed(mut self, ws: Option<Vec<isize>>, vs: Option<Vec<isize>>) -> Self {
+        self.ws = ws;
+        self.vs = vs;
+        self
+    }
+    // the hints the gadgets in code::arith ask for, worked out by their helpers.
+    pub fn with_hints(mut self, hints: Vec<BigUint>) -> Self {
+        self.wh = Some(hints.iter().map(|h| h.to_string()).collect());
+        self
+    }
+    // checks the knowledge assigns every input the circuit in the crs declares, so that a witness
+    // read from outside can be turned away before zkProof::new() would panic on it.
+    pub fn check<C, T, U, V>(&self, crs: &C) -> Result<(), String>
+    where
+        C: Common<T, U, V>,
+        T: Field + Copy + From<usize> + FromStr,
+    {
+        let assignments: Vec<T> = self.assignments()?;
+        let (code, _, _, _) = crs.clone().get();
+        match groth16::weights(&code, &assignments) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!("the witness does not fit the circuit, {} values were given", assignments.len())),
+        }
+    }
+    // the field values handed to the circuit, in the order wb, vb, wn, vn, ws, vs, wh.
+    fn assignments<T>(&self) -> Result<Vec<T>, String>
+    where
+        T: Field + From<usize> + FromStr,
+    {
+        let mut assignments: Vec<T> = Vec::new();
+        if self.wb.is_some() || self.vb.is_some() {
+            match self.ut {
+                Some(ref tag) if into_field::width(tag).is_some() => {},
+                Some(ref tag) => return Err(format!("{} is not a bit width", tag)),
+                None => {},
+            }
+        }
+        match self.wb {
+            Some(ref vec) => {
+                match self.ut {
+                    Some(ref tag) => assignments.append(&mut vec.clone().collect_bits(tag)),
+                    None => {},
+                };
+            },
+            None => {},
+        }
+        match self.vb {
+            Some(ref vec) => {
+                match self.ut {
+                    Some(ref tag) => assignments.append(&mut vec.clone().collect_bits(tag)),
+                    None => {},
+                };
+            },
+            None => {},
+        }
+        match self.wn {
+            Some(ref vec) => assignments.append(&mut vec.clone().collect_nums()),
+            None => {},
+        }
+        match self.vn {
+            Some(ref vec) => assignments.append(&mut vec.clone().collect_nums()),
+            None => {},
+        }
+        match self.ws {
+            Some(ref vec) => assignments.append(&mut vec.clone().collect_nums()),
+            None => {},
+        }
+        match self.vs {
+            Some(ref vec) => assignments.append(&mut vec.clone().collect_nums()),
+            None => {},
+        }
+        match self.wh {
+            Some(ref vec) => {
+                for h in vec.iter() {
+                    match T::from_str(h) {
+                        Ok(x) => assignments.push(x),
+                        Err(_) => return Err(format!("{} is not a field element", h)),
+                    }
+                }
+            },
+            None => {},
+        }
+        Ok(assignments)
+    }
 }
 
+// vs holds the public signed nums, checked after the bits, and vf the public field elements in decimal, checked last.
 pub struct Marker {
     vn: Option<Vec<usize>>,
     vb: Option<Vec<usize>>,
     ut: Option<String>,
+    vs: Option<Vec<isize>>,
+    vf: Option<Vec<String>>,
 }
 
 impl zkVerify for Marker {
@@ -142,6 +236,7 @@ impl zkVerify for Marker {
         T: Field 
             + From<usize> 
             + Copy 
+            + FromStr
             + EllipticEncryptable<G1 = U, G2 = V, GT = W>,
         U: Sum,
         V: Add<Output=V> 
@@ -164,6 +259,21 @@ impl zkVerify for Marker {
             },
             None => {}, 
         }
+        match self.vs {
+            Some(vec) => inputs.append(&mut vec.collect_nums()),
+            None => {},
+        }
+        match self.vf {
+            Some(vec) => {
+                for f in vec.iter() {
+                    match T::from_str(f) {
+                        Ok(x) => inputs.push(x),
+                        Err(_) => return false,
+                    }
+                }
+            },
+            None => {},
+        }
         let (_, _, sg1, sg2) = crs.get();
         groth16::verify::<CoefficientPoly<T>, _, _, _, _>(
             (sg1, sg2),
@@ -183,8 +293,18 @@ impl Marker {
             vn: vn,
             vb: vb,
             ut: ut,
+            vs: None,
+            vf: None,
         }
     }
+    pub fn with_signed(mut self, vs: Option<Vec<isize>>) -> Self {
+        self.vs = vs;
+        self
+    }
+    pub fn with_elements(mut self, vf: Option<Vec<String>>) -> Self {
+        self.vf = vf;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -217,6 +337,9 @@ mod tests {
                 vn: None,
                 vb: None,
                 ut: None,
+                ws: None,
+                vs: None,
+                wh: None,
             };
             let crs: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::read(
                 &read_to_string("src/tests/files/crs/sample.crs").unwrap()
@@ -229,6 +352,8 @@ mod tests {
                 vb: None,
                 vn: Some(vec![a]),
                 ut: None,
+                vs: None,
+                vf: None,
             };
             let crs: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::read(
                 &read_to_string("src/tests/files/crs/sample.crs").unwrap()
@@ -5,6 +5,14 @@ pub mod knowledge;
 pub mod common;
 pub mod interface;
 pub mod crypto;
+pub mod replay;
+pub mod report;
+pub mod server;
+pub mod cosign;
+pub mod trust;
+pub mod seal;
+#[cfg(test)]
+mod tests;
 
 pub use zksnark::{
     *, 
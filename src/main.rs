@@ -0,0 +1,1028 @@
+use zero_orb::{
+    FrLocal, G1Local, G2Local, GtLocal,
+    Knowledge, Andromeda, GoZero, CommonReference, Common,
+    crypto::{
+        Algorithm,
+        cert::Certificate,
+        derive::{self, ExtendedKey},
+        keystore::{self, KeyHandle, KeyStore},
+        signer::{self, PipeSigner},
+        x25519::SealKey,
+    },
+    interface::{BackPack, MarkZero, MarkFresh, MarkTrusted},
+    replay::{FileStore, Window},
+    report::VerificationReport,
+    seal::Sealed,
+    server::{self, Verifier},
+    trust::TrustPolicy,
+    code::comparator::ComparatorSpec,
+    cosign::Policy,
+};
+use zksnark::groth16::circuit::{ASTParser, TryParse, dummy_rep::DummyRep};
+use serde_derive::Deserialize;
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{read_to_string, File},
+    io::Write,
+    net::TcpListener,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+type Crs = CommonReference<FrLocal, G1Local, G2Local>;
+type Orb = BackPack<Crs, FrLocal, G1Local, G2Local, GtLocal>;
+
+// exit codes: scripts should branch on these rather than on the output.
+const OK: i32 = 0;
+const REJECTED: i32 = 1;
+const USAGE: i32 = 2;
+const FAILURE: i32 = 3;
+
+const HELP: &str = "usage: zero_orb <command> [args]
+
+commands:
+    setup <code.zk> [--out crs.json]
+        builds a CommonReference from a .zk program.
+    keygen [--out key.pk8 | --out key.pem | --store dir --name id] [--alg ed25519|p256|p384] [--passphrase-env VAR]
+        generates a key pair, Ed25519 unless --alg says otherwise, as PKCS#8 DER, or as PEM when the file ends in .pem,
+        or as a named identity in a key store. pem and stored keys are encrypted with the
+        passphrase held in the environment variable VAR.
+    derive --seed-env VAR (--path m/0'/1' | --device n [--circuit n | --crs crs.json]) [--out key.pk8 | --out key.pem | --store dir --name id]
+            [--passphrase-env VAR]
+        derives an Ed25519 key from the hex seed held in the environment variable VAR, along --path
+        or along m/31343'/device'/circuit', the circuit numbered by its crs when --crs is given.
+        prints the public key, or writes the key out as keygen does.
+    prove --crs crs.json --witness witness.json (--key key.pk8 | --store dir --name id | --signer program [--signer-arg arg]... [--signer-timeout 30])
+            [--passphrase-env VAR] [--chain chain.json] [--out backpack.json]
+        proves the witness and signs the proof into a BackPack.
+        the witness holds the Knowledge fields wb, vb, wn, vn, ut, ws, vs and wh, the public nums in out,
+        and the public field elements, such as a digest, in vf.
+        --signer starts program, with each --signer-arg in turn as its arguments, and has it sign over its stdin
+        and stdout, so the key stays with it. a signer that does not answer within --signer-timeout seconds is killed.
+        --chain carries a json list of certificates for the signing key, leaf first.
+    signer (--key key.pk8 | --store dir --name id) [--passphrase-env VAR]
+        runs a signing daemon on stdin and stdout for prove --signer.
+    cert --subject name (--puk hex --alg ed25519|p256|p384 | --self true) --issuer name (--key ca.pk8 | --store dir --name id)
+            [--passphrase-env VAR] [--ca true] [--days 365] [--attrs k=v,k=v] [--out cert.json]
+        issues a certificate binding the subject's public key to its name, signed by the issuer's key.
+        --self true makes a root, the issuer's key certifying itself.
+    seal-keygen --out recipient.key
+        generates an X25519 key for receiving sealed BackPacks and prints its public key.
+    seal --backpack backpack.json --to hex [--out sealed.json]
+        encrypts a BackPack to the recipient's X25519 public key.
+    verify --crs trusted.json --backpack backpack.json [--seal-key recipient.key] [--nonces nonces.txt] [--max-age s] [--max-skew s] [--trust trust.json]
+            [--policy policy.json]
+        checks a BackPack against a trusted crs, against the seen nonces when --nonces is given,
+        and its signing key against the allowed, revoked and rotated keys when --trust is given.
+        with either, the issue time must fall within --max-age seconds before now and --max-skew after.
+        --policy demands that the BackPack asks for exactly this m of n co-signing policy and meets it.
+        --seal-key opens a sealed BackPack first.
+    inspect <backpack.json | crs.json>
+        pretty-prints a BackPack or a CommonReference.
+    gen-comparator <8|16|32|64|i8|i16|i32|i64> <comp|le|gt|ge|eq|ne|min|max|range> <out.zk>
+        writes a comparator program, over signed values for the i widths.
+    serve --crs-dir dir [--socket path] [--http port] [--nonces nonces.txt] [--max-age s] [--max-skew s] [--trust trust.json] [--policy policy.json] [--workers 8]
+        verifies BackPacks against every crs in dir, one json per line on a unix socket
+        and POST /verify on 127.0.0.1:port, answering --workers connections at a time on each.
+
+exit codes: 0 ok or valid, 1 rejected, 2 usage, 3 io or parse failure.
+output is json on stdout, errors are json on stderr.";
+
+enum CliError {
+    Usage(String),
+    Failure(String),
+}
+
+// the public inputs travel alongside the Knowledge: vb, vs and ut are shared, out holds the public nums
+// and vf the public field elements, such as a digest, in decimal.
+#[derive(Deserialize)]
+struct Witness {
+    wb: Option<Vec<usize>>,
+    vb: Option<Vec<usize>>,
+    wn: Option<Vec<usize>>,
+    vn: Option<Vec<usize>>,
+    ut: Option<String>,
+    ws: Option<Vec<isize>>,
+    vs: Option<Vec<isize>>,
+    wh: Option<Vec<String>>,
+    out: Option<Vec<usize>>,
+    vf: Option<Vec<String>>,
+}
+
+// a flag given more than once keeps its last value in flags, and every value in order in repeated.
+// env looks up the variables that --passphrase-env and --seed-env name, which run looks for in the
+// process environment.
+struct Args {
+    positional: Vec<String>,
+    flags: HashMap<String, String>,
+    repeated: Vec<(String, String)>,
+    env: Box<dyn Fn(&str) -> Option<String>>,
+}
+
+impl Args {
+    fn parse<E>(args: &[String], env: E) -> Result<Self, CliError>
+    where
+        E: Fn(&str) -> Option<String> + 'static,
+    {
+        let mut positional = Vec::new();
+        let mut flags = HashMap::new();
+        let mut repeated = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.starts_with("--") {
+                true => match iter.next() {
+                    Some(value) => {
+                        flags.insert(arg[2..].to_string(), value.clone());
+                        repeated.push((arg[2..].to_string(), value.clone()));
+                    },
+                    None => return Err(CliError::Usage(format!("{} expects a value", arg))),
+                },
+                false => positional.push(arg.clone()),
+            }
+        }
+        Ok(Args {
+            positional: positional,
+            flags: flags,
+            repeated: repeated,
+            env: Box::new(env),
+        })
+    }
+
+    fn env(&self, var: &str) -> Result<String, CliError> {
+        (self.env)(var).ok_or(CliError::Usage(format!("the environment variable {} is not set", var)))
+    }
+
+    fn flag(&self, name: &str) -> Result<&String, CliError> {
+        self.flags.get(name).ok_or(CliError::Usage(format!("missing --{}", name)))
+    }
+
+    fn all(&self, name: &str) -> Vec<String> {
+        self.repeated.iter()
+            .filter(|(flag, _)| flag == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn position(&self, i: usize, name: &str) -> Result<&String, CliError> {
+        self.positional.get(i).ok_or(CliError::Usage(format!("missing <{}>", name)))
+    }
+
+    fn number(&self, name: &str, default: u64) -> Result<u64, CliError> {
+        match self.flags.get(name) {
+            Some(value) => value.parse::<u64>().map_err(|_| CliError::Usage(format!("--{} expects a number", name))),
+            None => Ok(default),
+        }
+    }
+}
+
+fn main() {
+    std::process::exit(run(std::env::args().skip(1).collect()))
+}
+
+fn run(args: Vec<String>) -> i32 {
+    run_with(args, |var: &str| std::env::var(var).ok())
+}
+
+// as run, with the variables named by --passphrase-env and --seed-env looked up through env.
+fn run_with<E>(args: Vec<String>, env: E) -> i32
+where
+    E: Fn(&str) -> Option<String> + 'static,
+{
+    let result = match args.split_first() {
+        Some((command, rest)) => match Args::parse(rest, env) {
+            Ok(args) => match command.as_ref() {
+                "setup" => setup(&args),
+                "keygen" => keygen(&args),
+                "derive" => derive_key(&args),
+                "prove" => prove(&args),
+                "signer" => sign_daemon(&args),
+                "verify" => verify(&args),
+                "seal-keygen" => seal_keygen(&args),
+                "seal" => seal(&args),
+                "cert" => cert(&args),
+                "inspect" => inspect(&args),
+                "gen-comparator" => gen_comparator(&args),
+                "serve" => serve(&args),
+                "help" | "--help" | "-h" => {
+                    println!("{}", HELP);
+                    Ok(OK)
+                },
+                _ => Err(CliError::Usage(format!("unknown command {}", command))),
+            },
+            Err(e) => Err(e),
+        },
+        None => Err(CliError::Usage(String::from("missing command"))),
+    };
+    match result {
+        Ok(code) => code,
+        Err(CliError::Usage(msg)) => {
+            eprintln!("{}", json!({ "error": msg, "usage": HELP }));
+            USAGE
+        },
+        Err(CliError::Failure(msg)) => {
+            eprintln!("{}", json!({ "error": msg }));
+            FAILURE
+        },
+    }
+}
+
+fn setup(args: &Args) -> Result<i32, CliError> {
+    let path = args.position(0, "code.zk")?;
+    let code = read_text(path)?;
+    let rep: Result<DummyRep<FrLocal>, _> = ASTParser::try_parse(&code);
+    if rep.is_err() {
+        return Err(CliError::Failure(format!("parsing {}: the program does not parse", path)))
+    }
+    let crs: Crs = CommonReference::new(code);
+    emit(args, &crs)?;
+    Ok(OK)
+}
+
+fn keygen(args: &Args) -> Result<i32, CliError> {
+    let alg = match args.flags.get("alg") {
+        Some(alg) => alg.parse::<Algorithm>().map_err(CliError::Usage)?,
+        None => Algorithm::Ed25519,
+    };
+    let name = args.flags.get("name").map_or("keygen", String::as_str);
+    write_key(args, &KeyHandle::generate(name, alg))
+}
+
+// derived keys are exported as their public key unless --out or --store asks for the secret.
+fn derive_key(args: &Args) -> Result<i32, CliError> {
+    let var = args.flag("seed-env")?;
+    let seed = args.env(var)
+        .and_then(|seed| unhex(seed.trim()).ok_or(CliError::Usage(format!("{} expects a hex seed", var))))?;
+    let path = match (args.flags.get("path"), args.flags.get("device")) {
+        (Some(path), _) => path.parse::<derive::Path>().map_err(CliError::Usage)?,
+        (None, Some(_)) => {
+            let index = |name: &str| match args.number(name, 0)? {
+                i if i < u64::from(derive::HARDENED) => Ok(i as u32),
+                _ => Err(CliError::Usage(format!("--{} expects a number below {}", name, derive::HARDENED))),
+            };
+            let circuit = match args.flags.get("crs") {
+                Some(crs) => derive::circuit_index(&parse::<Crs>(crs)?),
+                None => index("circuit")?,
+            };
+            derive::Path::orb(index("device")?, circuit).map_err(CliError::Usage)?
+        },
+        (None, None) => return Err(CliError::Usage(String::from("derive expects --path or --device"))),
+    };
+    let name = args.flags.get("name").map_or("derived", String::as_str);
+    let key = ExtendedKey::master(&seed)
+        .map_err(|e| CliError::Usage(e.to_string()))?
+        .derive(&path)
+        .key_handle(name);
+    match (args.flags.get("out"), args.flags.get("store")) {
+        (None, None) => {
+            println!("{}", json!({ "path": path.to_string(), "alg": key.algorithm().to_string(), "puk": hex(&key.public_key()) }));
+            Ok(OK)
+        },
+        (_, _) => write_key(args, &key),
+    }
+}
+
+// writes the key to --store under its name, to --out as pem or PKCS#8 DER, or as DER to stdout.
+fn write_key(args: &Args, key: &KeyHandle) -> Result<i32, CliError> {
+    let passphrase = passphrase(args)?;
+    let alg = key.algorithm();
+    if let Some(dir) = args.flags.get("store") {
+        let name = args.flag("name")?;
+        KeyStore::open(dir)
+            .and_then(|store| store.insert(key, passphrase.as_ref().map(String::as_str)))
+            .map_err(|e| CliError::Failure(format!("storing {} in {}: {}", name, dir, e)))?;
+        println!("{}", json!({ "store": dir, "name": name, "alg": alg.to_string(), "puk": hex(&key.public_key()) }));
+        return Ok(OK)
+    }
+    match (args.flags.get("out"), passphrase) {
+        (Some(path), passphrase) if path.ends_with(".pem") => {
+            let pem = match passphrase {
+                Some(passphrase) => key.to_encrypted_pem(&passphrase),
+                None => key.to_pem(),
+            };
+            write_secret(path, pem.as_bytes())?;
+            println!("{}", json!({ "out": path, "alg": alg.to_string(), "puk": hex(&key.public_key()) }));
+        },
+        (_, Some(_)) => return Err(CliError::Usage(String::from("only pem and stored keys can be encrypted"))),
+        (Some(path), None) => {
+            write_secret(path, key.to_der())?;
+            println!("{}", json!({ "out": path, "alg": alg.to_string(), "puk": hex(&key.public_key()) }));
+        },
+        (None, None) => std::io::stdout()
+            .write_all(key.to_der())
+            .map_err(|e| CliError::Failure(format!("writing the key to stdout: {}", e)))?,
+    }
+    Ok(OK)
+}
+
+// passphrases are read from the environment so they stay out of the process list.
+fn passphrase(args: &Args) -> Result<Option<String>, CliError> {
+    match args.flags.get("passphrase-env") {
+        Some(var) => args.env(var).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn signing_key(args: &Args) -> Result<KeyHandle, CliError> {
+    let passphrase = passphrase(args)?;
+    let passphrase = passphrase.as_ref().map(String::as_str);
+    match args.flags.get("store") {
+        Some(dir) => {
+            let name = args.flag("name")?;
+            KeyStore::open(dir)
+                .and_then(|store| store.load(name, passphrase))
+                .map_err(|e| CliError::Failure(format!("loading {} from {}: {}", name, dir, e)))
+        },
+        None => {
+            let path = args.flag("key")?;
+            KeyHandle::read("key", path, passphrase)
+                .map_err(|e| CliError::Failure(format!("reading {}: {}", path, e)))
+        },
+    }
+}
+
+fn prove(args: &Args) -> Result<i32, CliError> {
+    let crs: Crs = parse(args.flag("crs")?)?;
+    let path = args.flag("witness")?;
+    let witness: Witness = parse(path)?;
+    let knowledge = Knowledge::into(
+        witness.wb,
+        witness.vb.clone(),
+        witness.wn,
+        witness.vn,
+        witness.ut.clone(),
+    ).with_signed(witness.ws, witness.vs.clone());
+    let knowledge = match witness.wh {
+        Some(wh) => knowledge.with_hints(wh.iter()
+            .map(|h| h.parse::<num::BigUint>().map_err(|_| CliError::Usage(format!("hint {} is not a number", h))))
+            .collect::<Result<Vec<_>, CliError>>()?),
+        None => knowledge,
+    };
+    let elements = match witness.vf {
+        Some(vf) => Some(vf.iter()
+            .map(|f| f.parse::<num::BigUint>().map_err(|_| CliError::Usage(format!("element {} is not a number", f))))
+            .collect::<Result<Vec<_>, CliError>>()?),
+        None => None,
+    };
+    knowledge.check(&crs).map_err(|e| CliError::Failure(format!("checking {}: {}", path, e)))?;
+    let chain: Option<Vec<Certificate>> = match args.flags.get("chain") {
+        Some(path) => Some(parse(path)?),
+        None => None,
+    };
+    let andromeda = match args.flags.get("signer") {
+        Some(program) => {
+            let timeout = Duration::from_secs(args.number("signer-timeout", signer::TIMEOUT.as_secs())?);
+            let signer = PipeSigner::spawn_timeout(program, &args.all("signer-arg"), timeout)
+                .map_err(|e| CliError::Failure(format!("starting {}: {}", program, e)))?;
+            Andromeda::into_signer(crs, knowledge, witness.out, witness.vb, witness.ut, signer)
+        },
+        None => Andromeda::into_bits(crs, knowledge, witness.out, witness.vb, witness.ut, signing_key(args)?),
+    };
+    let andromeda = match witness.vs {
+        Some(vs) => andromeda.with_signed(vs),
+        None => andromeda,
+    };
+    let andromeda = match elements {
+        Some(vf) => andromeda.with_elements(vf),
+        None => andromeda,
+    };
+    let andromeda = match chain {
+        Some(chain) => andromeda.certified(chain),
+        None => andromeda,
+    };
+    let orb: Orb = andromeda.try_go()
+        .map_err(|e| CliError::Failure(e.to_string()))?;
+    emit(args, &orb)?;
+    Ok(OK)
+}
+
+// the key is only ever loaded here. the daemon exits when prove closes its stdin.
+fn sign_daemon(args: &Args) -> Result<i32, CliError> {
+    let mut key = signing_key(args)?;
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    signer::serve(&mut key, stdin.lock(), stdout.lock())
+        .map_err(|e| CliError::Failure(e.to_string()))?;
+    Ok(OK)
+}
+
+// the orb must carry exactly the trusted crs, otherwise the proof would be checked against the prover's own setup.
+fn verify(args: &Args) -> Result<i32, CliError> {
+    let trusted: Crs = parse(args.flag("crs")?)?;
+    let backpack = args.flag("backpack")?;
+    let orb: Orb = match args.flags.get("seal-key") {
+        Some(key) => parse::<Sealed>(backpack)?
+            .open(&seal_key(key)?)
+            .map_err(|e| CliError::Failure(format!("opening {}: {}", backpack, e)))?,
+        None => parse(backpack)?,
+    };
+    let trust = trust_policy(args)?;
+    let approval = approval_policy(args)?;
+    let unapproved = approval.as_ref().and_then(|policy| orb.requires(policy).err());
+    let report = match (serde_json::to_string(&orb.crs).ok() == serde_json::to_string(&trusted).ok(), unapproved) {
+        (false, _) => VerificationReport::untrusted(Some(String::from("the BackPack does not carry the trusted crs"))),
+        (true, Some(msg)) => VerificationReport::unapproved(Some(msg)),
+        (true, None) => match (args.flags.get("nonces"), trust) {
+            (Some(path), trust) => {
+                let mut store = FileStore::open(path)
+                    .map_err(|e| CliError::Failure(format!("opening {}: {}", path, e)))?;
+                let window = window(args)?;
+                match trust {
+                    Some(policy) => orb.report_trusted_fresh(&policy, &mut store, &window, zero_orb::replay::now()),
+                    None => orb.report_fresh(&mut store, &window, zero_orb::replay::now()),
+                }
+            },
+            (None, Some(policy)) => orb.report_trusted(&policy, &window(args)?, zero_orb::replay::now()),
+            (None, None) => orb.report(),
+        },
+    };
+    println!("{}", serde_json::to_string(&report).expect("zero_orb::verify() serializing the VerificationReport"));
+    match report.is_valid() {
+        true => Ok(OK),
+        false => Ok(REJECTED),
+    }
+}
+
+fn seal_keygen(args: &Args) -> Result<i32, CliError> {
+    let key = SealKey::generate();
+    let path = args.flag("out")?;
+    write_secret(path, key.to_bytes())?;
+    println!("{}", json!({ "out": path, "puk": hex(&key.public_key()) }));
+    Ok(OK)
+}
+
+fn seal(args: &Args) -> Result<i32, CliError> {
+    let orb: Orb = parse(args.flag("backpack")?)?;
+    let to = unhex(args.flag("to")?).ok_or(CliError::Usage(String::from("--to expects hex")))?;
+    let sealed = Sealed::seal(&orb, &to).map_err(|e| CliError::Usage(e.to_string()))?;
+    emit(args, &sealed)?;
+    Ok(OK)
+}
+
+fn seal_key(path: &str) -> Result<SealKey, CliError> {
+    std::fs::read(path)
+        .map_err(|e| CliError::Failure(format!("reading {}: {}", path, e)))
+        .and_then(|bytes| SealKey::from_bytes(&bytes).map_err(|e| CliError::Failure(format!("reading {}: {}", path, e))))
+}
+
+fn cert(args: &Args) -> Result<i32, CliError> {
+    let subject = args.flag("subject")?;
+    let issuer = args.flag("issuer")?;
+    let mut key = signing_key(args)?;
+    let (alg, puk) = match (args.flags.get("self").map(String::as_str), args.flags.get("puk")) {
+        (Some("true"), _) => (key.algorithm(), key.public_key()),
+        (_, Some(puk)) => (
+            args.flag("alg")?.parse::<Algorithm>().map_err(CliError::Usage)?,
+            unhex(puk).ok_or(CliError::Usage(String::from("--puk expects hex")))?,
+        ),
+        (_, None) => return Err(CliError::Usage(String::from("cert expects --puk or --self true"))),
+    };
+    if puk.len() != alg.public_key_len() {
+        return Err(CliError::Usage(format!("a {} public key is {} bytes", alg, alg.public_key_len())))
+    }
+    let mut attributes = BTreeMap::new();
+    if let Some(attrs) = args.flags.get("attrs") {
+        for attr in attrs.split(',') {
+            match attr.find('=') {
+                Some(i) => { attributes.insert(attr[..i].to_string(), attr[i + 1..].to_string()); },
+                None => return Err(CliError::Usage(format!("--attrs expects k=v, found {}", attr))),
+            }
+        }
+    }
+    let now = zero_orb::replay::now();
+    let certificate = Certificate::into(
+        subject,
+        alg,
+        puk,
+        attributes,
+        now,
+        now + args.number("days", 365)? * 86_400,
+        args.flags.get("ca").map(String::as_str) == Some("true"),
+    ).sign(issuer, &mut key).map_err(|e| CliError::Failure(e.to_string()))?;
+    emit(args, &certificate)?;
+    Ok(OK)
+}
+
+// the freshness window around now, for --nonces and --trust alike.
+fn window(args: &Args) -> Result<Window, CliError> {
+    Ok(Window::into(
+        args.number("max-age", Window::default().max_age)?,
+        args.number("max-skew", Window::default().max_skew)?,
+    ))
+}
+
+fn trust_policy(args: &Args) -> Result<Option<TrustPolicy>, CliError> {
+    match args.flags.get("trust") {
+        Some(path) => TrustPolicy::read(path)
+            .map(Some)
+            .map_err(|e| CliError::Failure(format!("reading {}: {}", path, e))),
+        None => Ok(None),
+    }
+}
+
+// the co-signing policy every orb must ask for, as a threshold and the signers' alg and puk.
+fn approval_policy(args: &Args) -> Result<Option<Policy>, CliError> {
+    match args.flags.get("policy") {
+        Some(path) => {
+            let policy: Policy = parse(path)?;
+            policy.check().map_err(|e| CliError::Failure(format!("reading {}: {}", path, e)))?;
+            Ok(Some(policy))
+        },
+        None => Ok(None),
+    }
+}
+
+fn inspect(args: &Args) -> Result<i32, CliError> {
+    let path = args.position(0, "file")?;
+    let text = read_text(path)?;
+    let summary = match (serde_json::from_str::<Orb>(&text), serde_json::from_str::<Crs>(&text)) {
+        (Ok(orb), _) => json!({
+            "type": "BackPack",
+            "ver": orb.ver,
+            "vb": orb.vb,
+            "vs": orb.vs,
+            "vf": orb.vf,
+            "ut": orb.ut,
+            "iat": orb.iat,
+            "nonce": hex(&orb.nonce),
+            "alg": orb.alg.to_string(),
+            "puk": hex(&orb.puk),
+            "sig": hex(&orb.sig),
+            "cosign": orb.cosign.as_ref().map(|cosign| json!({
+                "threshold": cosign.policy.threshold,
+                "signers": cosign.policy.signers.iter().map(|signer| format!("{}:{}", signer.alg, hex(&signer.puk))).collect::<Vec<_>>(),
+                "signed": cosign.sigs.iter().map(|(i, _)| i).collect::<Vec<_>>(),
+            })),
+            "chain": orb.chain.as_ref().map(|chain| chain.iter()
+                .map(|cert| json!({ "subject": cert.subject, "issuer": cert.issuer, "not_after": cert.not_after, "ca": cert.ca }))
+                .collect::<Vec<_>>()
+            ),
+            "crs": describe(&orb.crs),
+        }),
+        (_, Ok(crs)) => describe(&crs),
+        (Err(_), Err(_)) => return Err(CliError::Failure(format!("{} is neither a BackPack nor a CommonReference", path))),
+    };
+    println!("{}", serde_json::to_string_pretty(&summary).expect("zero_orb::inspect() serializing the summary"));
+    Ok(OK)
+}
+
+fn gen_comparator(args: &Args) -> Result<i32, CliError> {
+    let width = args.position(0, "width")?;
+    let kind = args.position(1, "kind")?;
+    let out = args.position(2, "out.zk")?;
+    // an i in front, as in i8, compares two's complement signed values.
+    let (signed, bits) = match width.starts_with('i') {
+        true => (true, &width[1..]),
+        false => (false, width.as_str()),
+    };
+    let spec = bits.parse::<usize>()
+        .map_err(|_| format!("{} is not a width", width))
+        .and_then(|bits| match signed {
+            true => ComparatorSpec::signed(bits, kind.parse()?),
+            false => ComparatorSpec::into(bits, kind.parse()?),
+        })
+        .map_err(CliError::Usage)?;
+    write_bytes(out, spec.render().as_bytes())?;
+    println!("{}", json!({ "out": out, "width": spec.width, "kind": spec.kind.to_string(), "tag": spec.tag() }));
+    Ok(OK)
+}
+
+fn serve(args: &Args) -> Result<i32, CliError> {
+    let dir = args.flag("crs-dir")?;
+    let mut verifier = Verifier::load(dir)
+        .map_err(|e| CliError::Failure(format!("loading {}: {}", dir, e)))?;
+    match args.flags.get("nonces") {
+        Some(path) => {
+            let store = FileStore::open(path)
+                .map_err(|e| CliError::Failure(format!("opening {}: {}", path, e)))?;
+            verifier = verifier.with_replay(store, window(args)?);
+        },
+        None => verifier = verifier.with_window(window(args)?),
+    }
+    if let Some(policy) = trust_policy(args)? {
+        verifier = verifier.with_trust(policy);
+    }
+    if let Some(policy) = approval_policy(args)? {
+        verifier = verifier.with_approval(policy);
+    }
+    let verifier = Arc::new(verifier.with_workers(args.number("workers", server::WORKERS as u64)? as usize));
+    let mut servers = Vec::new();
+    if let Some(port) = args.flags.get("http") {
+        let listener = TcpListener::bind(format!("127.0.0.1:{}", port))
+            .map_err(|e| CliError::Failure(format!("binding 127.0.0.1:{}: {}", port, e)))?;
+        let verifier = verifier.clone();
+        servers.push(thread::spawn(move || server::serve_http(verifier, listener)));
+    }
+    if let Some(path) = args.flags.get("socket") {
+        servers.push(serve_socket(verifier.clone(), path)?);
+    }
+    if servers.is_empty() {
+        return Err(CliError::Usage(String::from("serve expects --socket, --http or both")))
+    }
+    println!("{}", json!({ "trusted": verifier.names(), "http": args.flags.get("http"), "socket": args.flags.get("socket") }));
+    for server in servers {
+        let _ = server.join();
+    }
+    Ok(OK)
+}
+
+#[cfg(unix)]
+fn serve_socket(verifier: Arc<Verifier>, path: &str) -> Result<thread::JoinHandle<()>, CliError> {
+    let _ = std::fs::remove_file(path);
+    let listener = std::os::unix::net::UnixListener::bind(path)
+        .map_err(|e| CliError::Failure(format!("binding {}: {}", path, e)))?;
+    Ok(thread::spawn(move || server::serve_unix(verifier, listener)))
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_: Arc<Verifier>, _: &str) -> Result<thread::JoinHandle<()>, CliError> {
+    Err(CliError::Usage(String::from("--socket needs a unix platform")))
+}
+
+fn describe(crs: &Crs) -> serde_json::Value {
+    json!({
+        "type": "CommonReference",
+        "code": crs.code,
+        "public_inputs": crs.qap.input,
+        "variables": crs.qap.u.len(),
+        "constraints": crs.qap.degree,
+    })
+}
+
+fn read_text(path: &str) -> Result<String, CliError> {
+    read_to_string(path).map_err(|e| CliError::Failure(format!("reading {}: {}", path, e)))
+}
+
+fn parse<X>(path: &str) -> Result<X, CliError>
+where
+    for <'de>
+    X: serde::Deserialize<'de>,
+{
+    serde_json::from_str(&read_text(path)?).map_err(|e| CliError::Failure(format!("parsing {}: {}", path, e)))
+}
+
+fn write_bytes(path: &str, bytes: &[u8]) -> Result<(), CliError> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(bytes))
+        .map_err(|e| CliError::Failure(format!("writing {}: {}", path, e)))
+}
+
+// keys go to a new file only the owner can read, as the KeyStore writes them.
+fn write_secret(path: &str, bytes: &[u8]) -> Result<(), CliError> {
+    keystore::write_secret(std::path::Path::new(path), bytes)
+        .map_err(|e| CliError::Failure(format!("writing {}: {}", path, e)))
+}
+
+// writes json to --out when given, otherwise to stdout.
+fn emit<X: serde::Serialize>(args: &Args, x: &X) -> Result<(), CliError> {
+    let text = serde_json::to_string(x).map_err(|e| CliError::Failure(format!("serializing output: {}", e)))?;
+    match args.flags.get("out") {
+        Some(path) => {
+            write_bytes(path, text.as_bytes())?;
+            println!("{}", json!({ "out": path }));
+        },
+        None => println!("{}", text),
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+}
+
+fn unhex(text: &str) -> Option<Box<[u8]>> {
+    if text.len() % 2 != 0 || !text.is_ascii() {
+        return None
+    }
+    (0..text.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect::<Option<Vec<u8>>>()
+        .map(Vec::into_boxed_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::path::PathBuf;
+    use zero_orb::crypto::{keystore::KeyHandle, x25519::SealKey};
+    use super::{run_with, parse, hex, Orb, OK, REJECTED, USAGE, FAILURE};
+
+    const PASSPHRASE: &str = "ZERO_ORB_TEST_PASSPHRASE";
+    const SEED: &str = "ZERO_ORB_TEST_SEED";
+
+    // a directory of its own for each test, holding the programs and witnesses they share,
+    // removed again when the test is done.
+    struct Scratch {
+        dir: PathBuf,
+    }
+
+    impl Scratch {
+        fn new(test: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("zero_orb_cli_{}_{}", test, std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            let scratch = Scratch {
+                dir: dir,
+            };
+            for (name, text) in vec![
+                ("mul.zk", "(in a b) (out x) (verify x) (program (= x (* a b)))"),
+                ("square.zk", "(in a b) (out x) (verify x) (program (= x (* a a)))"),
+                ("broken.zk", "(in a b) (out x) (program (= x (* a"),
+                ("good.json", r#"{"wn": [20, 5], "out": [100]}"#),
+                ("bad.json", r#"{"wn": [20, 5], "out": [90]}"#),
+                ("short.json", r#"{"wn": [20], "out": [100]}"#),
+            ] {
+                std::fs::write(scratch.path(name), text).unwrap();
+            }
+            scratch
+        }
+
+        fn path(&self, name: &str) -> String {
+            self.dir.join(name).to_str().unwrap().to_string()
+        }
+
+        // runs the command with the test passphrase and seed in a stand-in environment, leaving the
+        // process environment alone. arguments ending in a file name are taken to be in the directory.
+        fn cmd(&self, line: Vec<&str>) -> i32 {
+            let line = line.into_iter()
+                .map(|arg| match arg.starts_with('@') {
+                    true => self.path(&arg[1..]),
+                    false => arg.to_string(),
+                })
+                .collect();
+            run_with(line, |var: &str| match var {
+                PASSPHRASE => Some(String::from("hunter2")),
+                SEED => Some(String::from("000102030405060708090a0b0c0d0e0f")),
+                _ => None,
+            })
+        }
+
+        // the mul crs as crs.json and an Ed25519 key as key.pk8, which most tests start from.
+        fn crs_and_key(&self) {
+            assert_eq!(OK, self.cmd(vec!["setup", "@mul.zk", "--out", "@crs.json"]));
+            assert_eq!(OK, self.cmd(vec!["keygen", "--out", "@key.pk8"]));
+        }
+
+        fn prove(&self, crs: &str, witness: &str, key: Vec<&str>, out: &str) -> i32 {
+            self.cmd([vec!["prove", "--crs", crs, "--witness", witness, "--out", out], key].concat())
+        }
+
+        fn check(&self, crs: &str, orb: &str, flags: Vec<&str>) -> i32 {
+            self.cmd([vec!["verify", "--crs", crs, "--backpack", orb], flags].concat())
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    #[test]
+    fn test_setup() {
+        let s = Scratch::new("setup");
+        assert_eq!(OK, s.cmd(vec!["setup", "@mul.zk", "--out", "@crs.json"]));
+        assert_eq!(FAILURE, s.cmd(vec!["setup", "@broken.zk", "--out", "@broken.json"]));
+        assert_eq!(FAILURE, s.cmd(vec!["setup", "@missing.zk"]));
+    }
+
+    #[test]
+    fn test_keygen() {
+        let s = Scratch::new("keygen");
+        assert_eq!(OK, s.cmd(vec!["keygen", "--out", "@key.pk8"]));
+        // keys are only readable by their owner, and never written over.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(0o600, std::fs::metadata(s.path("key.pk8")).unwrap().permissions().mode() & 0o777);
+        }
+        assert_eq!(FAILURE, s.cmd(vec!["keygen", "--out", "@key.pk8"]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--out", "@key.pem", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--store", "@keys", "--name", "alice", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(USAGE, s.cmd(vec!["keygen", "--out", "@key.der", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(USAGE, s.cmd(vec!["keygen", "--out", "@unset.pem", "--passphrase-env", "ZERO_ORB_TEST_UNSET"]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--alg", "p256", "--out", "@p256.pk8"]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--alg", "p384", "--out", "@p384.pem", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(USAGE, s.cmd(vec!["keygen", "--alg", "rsa"]));
+    }
+
+    #[test]
+    fn test_derive() {
+        let s = Scratch::new("derive");
+        assert_eq!(OK, s.cmd(vec!["setup", "@mul.zk", "--out", "@crs.json"]));
+        let derive = |flags: Vec<&str>| s.cmd([vec!["derive", "--seed-env", SEED], flags].concat());
+        assert_eq!(OK, derive(vec!["--path", "m/0'/1'"]));
+        assert_eq!(OK, derive(vec!["--device", "3", "--crs", "@crs.json", "--out", "@derived.pk8"]));
+        assert_eq!(OK, derive(vec!["--device", "3", "--circuit", "12", "--store", "@keys", "--name", "device-3"]));
+        assert_eq!(USAGE, derive(vec!["--path", "m/x"]));
+        assert_eq!(USAGE, derive(vec!["--device", "2147483648"]));
+        assert_eq!(USAGE, derive(vec![]));
+        assert_eq!(USAGE, s.cmd(vec!["derive", "--seed-env", "ZERO_ORB_TEST_UNSET", "--path", "m/0'"]));
+    }
+
+    #[test]
+    fn test_prove_verify() {
+        // every kind of key proves an orb that verifies against its own crs and no other.
+        let s = Scratch::new("prove_verify");
+        s.crs_and_key();
+        assert_eq!(OK, s.cmd(vec!["setup", "@square.zk", "--out", "@other.json"]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--alg", "p256", "--out", "@p256.pk8"]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--alg", "p384", "--out", "@p384.pem", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--out", "@key.pem", "--passphrase-env", PASSPHRASE]));
+        assert_eq!(OK, s.cmd(vec!["keygen", "--store", "@keys", "--name", "alice", "--passphrase-env", PASSPHRASE]));
+        let keys = vec![
+            vec!["--key", "@key.pk8"],
+            vec!["--key", "@p256.pk8"],
+            vec!["--key", "@p384.pem", "--passphrase-env", PASSPHRASE],
+            vec!["--key", "@key.pem", "--passphrase-env", PASSPHRASE],
+            vec!["--store", "@keys", "--name", "alice", "--passphrase-env", PASSPHRASE],
+        ];
+        for key in keys {
+            assert_eq!(OK, s.prove("@crs.json", "@good.json", key.clone(), "@good.orb"));
+            assert_eq!(OK, s.prove("@crs.json", "@bad.json", key, "@bad.orb"));
+            assert_eq!(OK, s.check("@crs.json", "@good.orb", vec![]));
+            assert_eq!(REJECTED, s.check("@crs.json", "@bad.orb", vec![]));
+            assert_eq!(REJECTED, s.check("@other.json", "@good.orb", vec![]));
+        }
+        // a key that needs a passphrase, or a witness that does not fill the circuit, proves nothing.
+        assert_eq!(FAILURE, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pem"], "@locked.orb"));
+        assert_eq!(FAILURE, s.prove("@crs.json", "@short.json", vec!["--key", "@key.pk8"], "@short.orb"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signer_timeout() {
+        // the signer's arguments are passed as given, and a signer that never answers is killed.
+        let s = Scratch::new("signer_timeout");
+        assert_eq!(OK, s.cmd(vec!["setup", "@mul.zk", "--out", "@crs.json"]));
+        let start = std::time::Instant::now();
+        assert_eq!(FAILURE, s.prove("@crs.json", "@good.json", vec![
+            "--signer", "sh",
+            "--signer-arg", "-c",
+            "--signer-arg", "exec sleep 30",
+            "--signer-timeout", "1",
+        ], "@good.orb"));
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_seal() {
+        let s = Scratch::new("seal");
+        s.crs_and_key();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8"], "@good.orb"));
+        assert_eq!(OK, s.prove("@crs.json", "@bad.json", vec!["--key", "@key.pk8"], "@bad.orb"));
+        assert_eq!(OK, s.cmd(vec!["seal-keygen", "--out", "@recipient.key"]));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(0o600, std::fs::metadata(s.path("recipient.key")).unwrap().permissions().mode() & 0o777);
+        }
+        assert_eq!(FAILURE, s.cmd(vec!["seal-keygen", "--out", "@recipient.key"]));
+        let recipient = SealKey::from_bytes(&std::fs::read(s.path("recipient.key")).unwrap()).ok().unwrap();
+        let seal = |orb: &str, to: &[u8], out: &str| s.cmd(vec!["seal", "--backpack", orb, "--to", &hex(to), "--out", out]);
+        assert_eq!(OK, seal("@good.orb", &recipient.public_key(), "@good.sealed"));
+        assert_eq!(OK, seal("@bad.orb", &recipient.public_key(), "@bad.sealed"));
+        assert_eq!(OK, seal("@good.orb", &SealKey::generate().public_key(), "@other.sealed"));
+        assert_eq!(USAGE, seal("@good.orb", &[1, 2, 3], "@short.sealed"));
+        let unseal = |sealed: &str| s.check("@crs.json", sealed, vec!["--seal-key", "@recipient.key"]);
+        assert_eq!(OK, unseal("@good.sealed"));
+        assert_eq!(REJECTED, unseal("@bad.sealed"));
+        assert_eq!(FAILURE, unseal("@other.sealed"));
+        assert_eq!(FAILURE, unseal("@good.orb"));
+    }
+
+    #[test]
+    fn test_nonces() {
+        // an orb is accepted once, and its replay turned away.
+        let s = Scratch::new("nonces");
+        s.crs_and_key();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8"], "@good.orb"));
+        assert_eq!(OK, s.check("@crs.json", "@good.orb", vec!["--nonces", "@nonces.txt"]));
+        assert_eq!(REJECTED, s.check("@crs.json", "@good.orb", vec!["--nonces", "@nonces.txt"]));
+    }
+
+    #[test]
+    fn test_trust() {
+        let s = Scratch::new("trust");
+        s.crs_and_key();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8"], "@good.orb"));
+        let signed: Orb = parse(&s.path("good.orb")).ok().unwrap();
+        std::fs::write(s.path("trust.json"), json!({ "allowed": [{ "alg": signed.alg, "puk": signed.puk }] }).to_string()).unwrap();
+        std::fs::write(s.path("nobody.json"), "{}").unwrap();
+        let trusted = |trust: &str| s.check("@crs.json", "@good.orb", vec!["--trust", trust]);
+        assert_eq!(OK, trusted("@trust.json"));
+        assert_eq!(REJECTED, trusted("@nobody.json"));
+        assert_eq!(FAILURE, trusted("@missing.json"));
+    }
+
+    #[test]
+    fn test_policy() {
+        // an orb that does not ask for the demanded co-signing policy is rejected, and a policy that cannot be met is refused.
+        let s = Scratch::new("policy");
+        s.crs_and_key();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8"], "@good.orb"));
+        let signed: Orb = parse(&s.path("good.orb")).ok().unwrap();
+        let signer = json!({ "alg": signed.alg, "puk": signed.puk });
+        std::fs::write(s.path("policy.json"), json!({ "threshold": 1, "signers": [signer] }).to_string()).unwrap();
+        std::fs::write(s.path("unmeetable.json"), json!({ "threshold": 2, "signers": [signer] }).to_string()).unwrap();
+        assert_eq!(REJECTED, s.check("@crs.json", "@good.orb", vec!["--policy", "@policy.json"]));
+        assert_eq!(FAILURE, s.check("@crs.json", "@good.orb", vec!["--policy", "@unmeetable.json"]));
+    }
+
+    #[test]
+    fn test_cert() {
+        // a root certifies key.pk8 as alice, and an orb carrying that chain passes a policy holding only the root.
+        let s = Scratch::new("cert");
+        s.crs_and_key();
+        assert_eq!(OK, s.cmd(vec!["keygen", "--alg", "p384", "--out", "@root.pk8"]));
+        let issue = |flags: Vec<&str>, out: &str| s.cmd(
+            [vec!["cert"], flags, vec!["--key", "@root.pk8", "--issuer", "root", "--out", out]].concat()
+        );
+        assert_eq!(OK, issue(vec!["--subject", "root", "--self", "true", "--ca", "true"], "@root.cert"));
+        let puk = hex(&KeyHandle::read("key", s.path("key.pk8"), None).unwrap().public_key());
+        assert_eq!(OK, issue(vec!["--subject", "alice", "--alg", "ed25519", "--puk", &puk, "--attrs", "role=clinician"], "@alice.cert"));
+        assert_eq!(USAGE, issue(vec!["--subject", "alice", "--alg", "p256", "--puk", &puk], "@bad.cert"));
+        let read = |name: &str| serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(s.path(name)).unwrap()).unwrap();
+        std::fs::write(s.path("chain.json"), json!([read("alice.cert")]).to_string()).unwrap();
+        std::fs::write(s.path("roots.json"), json!({ "roots": [read("root.cert")] }).to_string()).unwrap();
+        std::fs::write(s.path("nobody.json"), "{}").unwrap();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8", "--chain", "@chain.json"], "@certified.orb"));
+        assert_eq!(OK, s.check("@crs.json", "@certified.orb", vec!["--trust", "@roots.json"]));
+        assert_eq!(REJECTED, s.check("@crs.json", "@certified.orb", vec!["--trust", "@nobody.json"]));
+        assert_eq!(OK, s.cmd(vec!["inspect", "@certified.orb"]));
+    }
+
+    #[test]
+    fn test_inspect() {
+        let s = Scratch::new("inspect");
+        s.crs_and_key();
+        assert_eq!(OK, s.prove("@crs.json", "@good.json", vec!["--key", "@key.pk8"], "@good.orb"));
+        assert_eq!(OK, s.cmd(vec!["inspect", "@good.orb"]));
+        assert_eq!(OK, s.cmd(vec!["inspect", "@crs.json"]));
+        assert_eq!(FAILURE, s.cmd(vec!["inspect", "@mul.zk"]));
+    }
+
+    #[test]
+    fn test_gen_comparator() {
+        let s = Scratch::new("gen_comparator");
+        let read = |name: &str| std::fs::read_to_string(s.path(name)).unwrap();
+        assert_eq!(OK, s.cmd(vec!["gen-comparator", "8", "comp", "@comp.zk"]));
+        assert!(read("comp.zk").contains("(out less)"));
+        assert_eq!(OK, s.cmd(vec!["gen-comparator", "64", "range", "@range.zk"]));
+        assert_eq!(OK, s.cmd(vec!["gen-comparator", "16", "ge", "@ge.zk"]));
+        assert!(read("ge.zk").contains("(out greater_equal)"));
+        assert_eq!(OK, s.cmd(vec!["gen-comparator", "i16", "comp", "@signed.zk"]));
+        assert_eq!(USAGE, s.cmd(vec!["gen-comparator", "i7", "comp", "@signed.zk"]));
+        assert_eq!(USAGE, s.cmd(vec!["gen-comparator", "7", "comp", "@comp.zk"]));
+        assert_eq!(USAGE, s.cmd(vec!["gen-comparator", "8", "more", "@comp.zk"]));
+    }
+
+    #[test]
+    fn test_signed() {
+        // -3 < 4, the signed values handed over beside their bits.
+        let s = Scratch::new("signed");
+        assert_eq!(OK, s.cmd(vec!["keygen", "--out", "@key.pk8"]));
+        assert_eq!(OK, s.cmd(vec!["gen-comparator", "i8", "comp", "@signed.zk"]));
+        assert_eq!(OK, s.cmd(vec!["setup", "@signed.zk", "--out", "@signed.json"]));
+        let signed = |vs: isize, out: &str| {
+            std::fs::write(
+                s.path("signed.witness"),
+                json!({ "wb": [253], "vb": [4], "ws": [-3], "vs": [vs], "ut": "i8", "out": [1] }).to_string()
+            ).unwrap();
+            assert_eq!(OK, s.prove("@signed.json", "@signed.witness", vec!["--key", "@key.pk8"], out));
+            s.check("@signed.json", out, vec![])
+        };
+        assert_eq!(OK, signed(4, "@signed.orb"));
+        assert_eq!(REJECTED, signed(-4, "@forged.orb"));
+    }
+
+    #[test]
+    fn test_hash() {
+        // a digest goes into the orb as a field element.
+        let s = Scratch::new("hash");
+        assert_eq!(OK, s.cmd(vec!["keygen", "--out", "@key.pk8"]));
+        let hash = zero_orb::code::mimc::HashSpec::into(1).unwrap();
+        std::fs::write(s.path("hash.zk"), hash.render()).unwrap();
+        assert_eq!(OK, s.cmd(vec!["setup", "@hash.zk", "--out", "@hash.json"]));
+        let digest = hash.builder().evaluate(&[42u32.into()]).unwrap().remove(0);
+        let committed = |vf: &str, out: &str| {
+            std::fs::write(s.path("hash.witness"), json!({ "wn": [42], "vf": [vf] }).to_string()).unwrap();
+            assert_eq!(OK, s.prove("@hash.json", "@hash.witness", vec!["--key", "@key.pk8"], out));
+            s.check("@hash.json", out, vec![])
+        };
+        assert_eq!(OK, committed(&digest.to_string(), "@hash.orb"));
+        assert_eq!(REJECTED, committed(&(digest + 1u32).to_string(), "@wrong.orb"));
+    }
+
+    #[test]
+    fn test_usage() {
+        let s = Scratch::new("usage");
+        assert_eq!(USAGE, s.cmd(vec!["prove", "--crs"]));
+        assert_eq!(USAGE, s.cmd(vec![]));
+        assert_eq!(USAGE, s.cmd(vec!["unknown"]));
+        assert_eq!(FAILURE, s.cmd(vec!["serve", "--crs-dir", "@missing"]));
+    }
+}
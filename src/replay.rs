@@ -0,0 +1,280 @@
+use ring::rand::{SecureRandom, SystemRandom};
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// the verifier remembers every nonce it has accepted inside the validity window.
+// nonces issued before the window opens can be forgotten, as the window alone rejects them.
+// a store that cannot record a nonce says so rather than accept the orb unrecorded.
+pub trait NonceStore {
+    // records the nonce, returning false if it has already been seen.
+    fn insert(&mut self, nonce: &[u8], iat: u64) -> io::Result<bool>;
+    // forgets every nonce issued before the cut-off.
+    fn prune(&mut self, before: u64) -> io::Result<()>;
+}
+
+// the reasons a well-formed orb can be turned away at the door.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    Invalid,
+    Stale,
+    Premature,
+    Replayed,
+    Unavailable,
+}
+
+// max_age is how long an orb stays valid after issue, max_skew is how far ahead of our clock an issuer may be.
+// both are in seconds.
+#[derive(Debug, Clone, Copy)]
+pub struct Window {
+    pub max_age: u64,
+    pub max_skew: u64,
+}
+
+impl Window {
+    pub fn into(max_age: u64, max_skew: u64) -> Self {
+        Window {
+            max_age: max_age,
+            max_skew: max_skew,
+        }
+    }
+
+    pub fn check(&self, iat: u64, now: u64) -> Result<(), Rejection> {
+        if iat > now.saturating_add(self.max_skew) {
+            return Err(Rejection::Premature)
+        }
+        if now > iat.saturating_add(self.max_age) {
+            return Err(Rejection::Stale)
+        }
+        Ok(())
+    }
+
+    // the earliest issue time still inside the window.
+    pub fn opens(&self, now: u64) -> u64 {
+        now.saturating_sub(self.max_age)
+    }
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Window::into(300, 30)
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    seen: HashMap<Box<[u8]>, u64>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NonceStore for MemoryStore {
+    fn insert(&mut self, nonce: &[u8], iat: u64) -> io::Result<bool> {
+        match self.seen.contains_key(nonce) {
+            true => Ok(false),
+            false => {
+                self.seen.insert(nonce.to_vec().into_boxed_slice(), iat);
+                Ok(true)
+            },
+        }
+    }
+    fn prune(&mut self, before: u64) -> io::Result<()> {
+        self.seen.retain(|_, iat| *iat >= before);
+        Ok(())
+    }
+}
+
+// keeps the seen nonces in memory and appends each new one to a file as "<hex nonce> <iat>",
+// so a restarted verifier does not accept orbs it has already seen. lines counts those in the file,
+// forgotten nonces included, so the file is only rewritten once it has grown to twice what it holds.
+pub struct FileStore {
+    path: PathBuf,
+    seen: MemoryStore,
+    lines: usize,
+}
+
+impl FileStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let (mut seen, mut lines) = (MemoryStore::new(), 0);
+        if path.as_ref().exists() {
+            for line in BufReader::new(File::open(&path)?).lines() {
+                let line = line?;
+                let mut parts = line.split(' ');
+                match (parts.next().and_then(from_hex), parts.next().and_then(|s| s.parse::<u64>().ok())) {
+                    (Some(nonce), Some(iat)) => { seen.insert(&nonce, iat)?; },
+                    (_, _) => return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("FileStore::open() malformed line in nonce file: {}", line)
+                    )),
+                }
+                lines += 1;
+            }
+        }
+        Ok(FileStore {
+            path: path.as_ref().to_path_buf(),
+            seen: seen,
+            lines: lines,
+        })
+    }
+
+    fn append(&self, nonce: &[u8], iat: u64) -> io::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{} {}", to_hex(nonce), iat)?;
+        file.sync_data()
+    }
+
+    // writes the nonces still held beside the file and renames it over, so a failure leaves the old file whole.
+    fn rewrite(&mut self) -> io::Result<()> {
+        let tmp = self.path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp)?;
+            for (nonce, iat) in self.seen.seen.iter() {
+                writeln!(file, "{} {}", to_hex(nonce), iat)?;
+            }
+            file.sync_data()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        self.lines = self.seen.seen.len();
+        Ok(())
+    }
+}
+
+impl NonceStore for FileStore {
+    // the nonce is only remembered once it is safely in the file.
+    fn insert(&mut self, nonce: &[u8], iat: u64) -> io::Result<bool> {
+        if self.seen.seen.contains_key(nonce) {
+            return Ok(false)
+        }
+        self.append(nonce, iat)?;
+        self.lines += 1;
+        self.seen.insert(nonce, iat)
+    }
+    // forgets the nonces issued before the cut-off, compacting the file once most of its lines are forgotten.
+    fn prune(&mut self, before: u64) -> io::Result<()> {
+        self.seen.prune(before)?;
+        match self.lines > 2 * self.seen.seen.len() + 64 {
+            true => self.rewrite(),
+            false => Ok(()),
+        }
+    }
+}
+
+// seconds since the unix epoch, the unit used for BackPack.iat.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("replay::now() system clock is set before the unix epoch")
+        .as_secs()
+}
+
+// 16 random bytes are plenty to never collide inside a validity window.
+pub fn nonce() -> Box<[u8]> {
+    let mut nonce = [0u8; 16];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .expect("replay::nonce() filling the nonce from SystemRandom");
+    nonce.to_vec().into_boxed_slice()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+}
+
+// slices two bytes at a time, so anything but ascii is turned away first rather than split mid character.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    match s.len() % 2 == 0 && s.is_ascii() {
+        true => (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+            .collect(),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::replay::{NonceStore, MemoryStore, FileStore, Window, Rejection};
+
+    #[test]
+    fn test_window() {
+        let window = Window::into(60, 5);
+        assert_eq!(Ok(()), window.check(1000, 1000));
+        assert_eq!(Ok(()), window.check(1000, 1060));
+        assert_eq!(Ok(()), window.check(1005, 1000));
+        assert_eq!(Err(Rejection::Stale), window.check(1000, 1061));
+        assert_eq!(Err(Rejection::Premature), window.check(1006, 1000));
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let mut store = MemoryStore::new();
+        assert!(store.insert(&[1, 2, 3], 100).unwrap());
+        assert!(!store.insert(&[1, 2, 3], 100).unwrap());
+        assert!(store.insert(&[4, 5, 6], 200).unwrap());
+        store.prune(150).unwrap();
+        assert!(store.insert(&[1, 2, 3], 100).unwrap());
+        assert!(!store.insert(&[4, 5, 6], 200).unwrap());
+    }
+
+    #[test]
+    fn test_file_store() {
+        let path = std::env::temp_dir().join(format!("zero_orb_nonces_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let mut store = FileStore::open(&path).unwrap();
+            assert!(store.insert(&[0xde, 0xad], 100).unwrap());
+            assert!(store.insert(&[0xbe, 0xef], 200).unwrap());
+            assert!(!store.insert(&[0xde, 0xad], 100).unwrap());
+        }
+        // a reopened store remembers what was seen before.
+        let mut store = FileStore::open(&path).unwrap();
+        assert!(!store.insert(&[0xde, 0xad], 100).unwrap());
+        store.prune(150).unwrap();
+        assert!(store.insert(&[0xde, 0xad], 100).unwrap());
+        assert!(!store.insert(&[0xbe, 0xef], 200).unwrap());
+
+        // the file is compacted once the forgotten nonces outnumber the rest.
+        for i in 0..100u8 {
+            store.insert(&[i], 300 + i as u64).unwrap();
+        }
+        let lines = || std::fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(103, lines());
+        store.prune(390).unwrap();
+        assert_eq!(10, lines());
+        let mut store = FileStore::open(&path).unwrap();
+        assert!(store.insert(&[0xde, 0xad], 100).unwrap());
+        assert!(!store.insert(&[99], 399).unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        // a store whose file cannot be written reports it rather than accept the nonce.
+        let dir = std::env::temp_dir().join(format!("zero_orb_nonce_dir_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut store = FileStore::open(&dir.join("missing").join("nonces")).unwrap();
+        assert!(store.insert(&[1], 100).is_err());
+        assert!(store.insert(&[1], 100).is_err());
+
+        // a line that is not hex, even one that is not ascii, is malformed rather than a panic.
+        for line in vec!["aéa 100\n", "zz 100\n", "abc 100\n", "ab\n"] {
+            let path = dir.join("nonces");
+            std::fs::write(&path, line).unwrap();
+            match FileStore::open(&path) {
+                Err(e) => assert_eq!(std::io::ErrorKind::InvalidData, e.kind()),
+                Ok(_) => panic!("a malformed nonce file was opened: {}", line),
+            }
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
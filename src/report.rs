@@ -0,0 +1,172 @@
+use serde_derive::{Serialize, Deserialize};
+use crate::replay::Rejection;
+
+// the outcome of a single check, Skipped when an earlier failure made it meaningless to run.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+// the first reason an orb was rejected, or Valid.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Valid,
+    UntrustedCrs,
+    MalformedCrs,
+    PublicInputMismatch,
+    BadSignature,
+    Unapproved,
+    BadProof,
+    Stale,
+    Premature,
+    Replayed,
+    StoreFailed,
+    UnknownKey,
+    RevokedKey,
+    RotatedKey,
+    BadCertificate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VerificationReport {
+    pub crs: Check,
+    pub inputs: Check,
+    pub signature: Check,
+    pub approval: Check,
+    pub proof: Check,
+    pub freshness: Check,
+    pub key: Check,
+    pub reason: Reason,
+    // the identity a certificate chain bound the signing key to, when the orb carried one.
+    #[serde(default)]
+    pub subject: Option<String>,
+    pub detail: Option<String>,
+}
+
+impl VerificationReport {
+    // the reason is taken from the first failing check, in the order the fields are declared.
+    // approval is Skipped for orbs that do not ask for co-signatures.
+    pub fn into(
+        crs: Check,
+        inputs: Check,
+        signature: Check,
+        approval: Check,
+        proof: Check,
+        detail: Option<String>,
+    ) -> Self {
+        let reason = match (crs, inputs, signature, approval, proof) {
+            (Check::Failed, _, _, _, _) => Reason::MalformedCrs,
+            (_, Check::Failed, _, _, _) => Reason::PublicInputMismatch,
+            (_, _, Check::Failed, _, _) => Reason::BadSignature,
+            (_, _, _, Check::Failed, _) => Reason::Unapproved,
+            (_, _, _, _, Check::Failed) => Reason::BadProof,
+            (_, _, _, _, _) => Reason::Valid,
+        };
+        VerificationReport {
+            crs: crs,
+            inputs: inputs,
+            signature: signature,
+            approval: approval,
+            proof: proof,
+            freshness: Check::Skipped,
+            key: Check::Skipped,
+            reason: reason,
+            detail: detail,
+            subject: None,
+        }
+    }
+
+    // an orb turned away on freshness alone, before any of the other checks were run.
+    pub fn rejected(rejection: Rejection, detail: Option<String>) -> Self {
+        VerificationReport {
+            crs: Check::Skipped,
+            inputs: Check::Skipped,
+            signature: Check::Skipped,
+            approval: Check::Skipped,
+            proof: Check::Skipped,
+            freshness: Check::Failed,
+            key: Check::Skipped,
+            reason: Reason::from(rejection),
+            detail: detail,
+            subject: None,
+        }
+    }
+
+    // an orb carrying a crs the verifier does not hold, turned away before any other check.
+    pub fn untrusted(detail: Option<String>) -> Self {
+        VerificationReport {
+            crs: Check::Failed,
+            inputs: Check::Skipped,
+            signature: Check::Skipped,
+            approval: Check::Skipped,
+            proof: Check::Skipped,
+            freshness: Check::Skipped,
+            key: Check::Skipped,
+            reason: Reason::UntrustedCrs,
+            detail: detail,
+            subject: None,
+        }
+    }
+
+    // an orb signed by a key the trust policy does not accept at its issue time, turned away before any other check.
+    pub fn distrusted(reason: Reason, detail: Option<String>) -> Self {
+        VerificationReport {
+            crs: Check::Skipped,
+            inputs: Check::Skipped,
+            signature: Check::Skipped,
+            approval: Check::Skipped,
+            proof: Check::Skipped,
+            freshness: Check::Skipped,
+            key: Check::Failed,
+            reason: reason,
+            detail: detail,
+            subject: None,
+        }
+    }
+
+    // an orb that does not ask for the co-signing policy the verifier demands, turned away before any other check.
+    pub fn unapproved(detail: Option<String>) -> Self {
+        VerificationReport {
+            crs: Check::Skipped,
+            inputs: Check::Skipped,
+            signature: Check::Skipped,
+            approval: Check::Failed,
+            proof: Check::Skipped,
+            freshness: Check::Skipped,
+            key: Check::Skipped,
+            reason: Reason::Unapproved,
+            detail: detail,
+            subject: None,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.reason == Reason::Valid
+    }
+}
+
+impl From<Rejection> for Reason {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::Stale => Reason::Stale,
+            Rejection::Premature => Reason::Premature,
+            Rejection::Replayed => Reason::Replayed,
+            Rejection::Unavailable => Reason::StoreFailed,
+            Rejection::Invalid => Reason::BadProof,
+        }
+    }
+}
+
+impl From<Reason> for Rejection {
+    fn from(reason: Reason) -> Self {
+        match reason {
+            Reason::Stale => Rejection::Stale,
+            Reason::Premature => Rejection::Premature,
+            Reason::Replayed => Rejection::Replayed,
+            Reason::StoreFailed => Rejection::Unavailable,
+            _ => Rejection::Invalid,
+        }
+    }
+}
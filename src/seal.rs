@@ -0,0 +1,180 @@
+use ring::{
+    aead,
+    agreement,
+    digest,
+    hkdf,
+    hmac,
+    rand::SystemRandom,
+};
+use serde_derive::{Serialize, Deserialize};
+use serde::{Serialize, de::DeserializeOwned};
+use std::fmt;
+use crate::{
+    crypto::x25519::SealKey,
+    interface::{MarkZero, ZeroRef},
+    report::VerificationReport,
+};
+
+const VERSION: &str = "zero_orb sealed v1";
+
+// a BackPack, or anything else serialisable, encrypted to one recipient's X25519 key.
+// each seal agrees a fresh ephemeral key with the recipient's, and the shared secret through HKDF-SHA256
+// keys ChaCha20-Poly1305. the key is never used twice, so the nonce is fixed at zero.
+// the orb travels whole inside, its signature with it, so the recipient verifies it as if it came in the clear.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Sealed {
+    pub epk: Box<[u8]>,
+    pub to: Box<[u8]>,
+    pub ct: Box<[u8]>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SealError {
+    BadKey(String),
+    WrongRecipient,
+    Tampered,
+    Malformed(String),
+}
+
+impl fmt::Display for SealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SealError::BadKey(msg) => write!(f, "the key cannot be agreed with: {}", msg),
+            SealError::WrongRecipient => write!(f, "the orb is sealed to another key"),
+            SealError::Tampered => write!(f, "the sealed orb does not open, it was altered in transit"),
+            SealError::Malformed(msg) => write!(f, "the opened orb does not parse: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SealError {}
+
+impl Sealed {
+    pub fn seal<X: Serialize>(x: &X, to: &[u8]) -> Result<Self, SealError> {
+        let plain = serde_json::to_vec(x).expect("Sealed::seal() serializing the orb");
+        let ephemeral = agreement::EphemeralPrivateKey::generate(&agreement::X25519, &SystemRandom::new())
+            .expect("Sealed::seal() generating an ephemeral key from SystemRandom");
+        let epk = ephemeral.compute_public_key()
+            .expect("Sealed::seal() computing the ephemeral public key")
+            .as_ref()
+            .to_vec()
+            .into_boxed_slice();
+        let key = agreement::agree_ephemeral(
+            ephemeral,
+            &agreement::X25519,
+            untrusted::Input::from(to),
+            SealError::BadKey(format!("a {} byte X25519 public key, or one of small order", to.len())),
+            |shared| Ok(derive(shared, &epk, to))
+        )?;
+        let key = aead::SealingKey::new(&aead::CHACHA20_POLY1305, &key).expect("Sealed::seal() building the sealing key");
+        let aad = [&epk[..], to].concat();
+        let mut in_out = plain;
+        in_out.extend_from_slice(&[0u8; 16][..aead::CHACHA20_POLY1305.tag_len()]);
+        let len = aead::seal_in_place(
+            &key,
+            aead::Nonce::assume_unique_for_key([0u8; 12]),
+            aead::Aad::from(&aad),
+            &mut in_out,
+            aead::CHACHA20_POLY1305.tag_len()
+        ).expect("Sealed::seal() sealing the orb");
+        in_out.truncate(len);
+        Ok(Sealed {
+            epk: epk,
+            to: to.to_vec().into_boxed_slice(),
+            ct: in_out.into_boxed_slice(),
+        })
+    }
+
+    pub fn open<X: DeserializeOwned>(&self, key: &SealKey) -> Result<X, SealError> {
+        if key.public_key() != self.to {
+            return Err(SealError::WrongRecipient)
+        }
+        let shared = key.agree(&self.epk)
+            .ok_or(SealError::BadKey(String::from("the ephemeral key is malformed or of small order")))?;
+        let key = aead::OpeningKey::new(&aead::CHACHA20_POLY1305, &derive(&shared, &self.epk, &self.to))
+            .expect("Sealed::open() building the opening key");
+        let aad = [&self.epk[..], &self.to[..]].concat();
+        let mut in_out = self.ct.to_vec();
+        let plain = aead::open_in_place(&key, aead::Nonce::assume_unique_for_key([0u8; 12]), aead::Aad::from(&aad), 0, &mut in_out)
+            .map_err(|_| SealError::Tampered)?;
+        serde_json::from_slice(plain).map_err(|e| SealError::Malformed(e.to_string()))
+    }
+
+    // opens the orb, then verifies it as MarkZero would, but only against the verifier's trusted crs:
+    // an orb checked against the crs it carries proves only that its sender ran a setup of their own.
+    pub fn report<O, A>(&self, key: &SealKey, trusted: &A) -> Result<VerificationReport, SealError>
+    where
+        O: MarkZero + ZeroRef + DeserializeOwned,
+        A: Serialize,
+    {
+        let orb = self.open::<O>(key)?;
+        match serde_json::to_string(trusted).ok() == Some(orb.get_crs_str()) {
+            true => Ok(orb.report()),
+            false => Ok(VerificationReport::untrusted(Some(String::from("the BackPack does not carry the trusted crs")))),
+        }
+    }
+    pub fn verify<O, A>(&self, key: &SealKey, trusted: &A) -> bool
+    where
+        O: MarkZero + ZeroRef + DeserializeOwned,
+        A: Serialize,
+    {
+        self.report::<O, A>(key, trusted).map_or(false, |report| report.is_valid())
+    }
+}
+
+// both public keys salt the derivation, binding the key to this sender and recipient.
+fn derive(shared: &[u8], epk: &[u8], to: &[u8]) -> [u8; 32] {
+    let salt = hmac::SigningKey::new(&digest::SHA256, &[epk, to].concat());
+    let mut key = [0u8; 32];
+    hkdf::extract_and_expand(&salt, shared, VERSION.as_bytes(), &mut key);
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use zksnark::groth16::fr::{FrLocal, G1Local, G2Local, GtLocal};
+    use crate::{
+        common::{CommonReference, Common},
+        crypto::{Algorithm, x25519::SealKey},
+        interface::BackPack,
+        report::Reason,
+        seal::{Sealed, SealError},
+        tests::{sample_crs, sample_orb},
+    };
+
+    type LocalBackPack = BackPack<CommonReference<FrLocal, G1Local, G2Local>, FrLocal, G1Local, G2Local, GtLocal>;
+
+    #[test]
+    fn test_sealed_backpack() {
+        let crs = sample_crs();
+        let orb = |x: usize| sample_orb(&crs, x, Algorithm::Ed25519.init_key_pair());
+        let (verifier, stranger) = (SealKey::generate(), SealKey::generate());
+
+        let sealed = Sealed::seal(&orb(100), &verifier.public_key()).unwrap();
+        // the public outputs are no longer readable on the wire.
+        let wire = serde_json::to_string(&sealed).unwrap();
+        assert!(!wire.contains("\"ver\""));
+        let sealed: Sealed = serde_json::from_str(&wire).unwrap();
+        assert_eq!(Reason::Valid, sealed.report::<LocalBackPack, _>(&verifier, &crs).unwrap().reason);
+        let opened: LocalBackPack = sealed.open(&verifier).unwrap();
+        assert_eq!(Some(vec![100]), opened.ver);
+
+        // a bad orb still fails inside its seal.
+        let bad = Sealed::seal(&orb(90), &verifier.public_key()).unwrap();
+        assert_eq!(Reason::BadProof, bad.report::<LocalBackPack, _>(&verifier, &crs).unwrap().reason);
+        // and a good one only passes against the crs the verifier trusts, not whichever it carries.
+        let other: CommonReference<FrLocal, G1Local, G2Local> = CommonReference::new(
+            String::from("(in a b) (out x) (verify x) (program (= x (* a a)))")
+        );
+        assert_eq!(Reason::UntrustedCrs, sealed.report::<LocalBackPack, _>(&verifier, &other).unwrap().reason);
+
+        assert_eq!(Err(SealError::WrongRecipient), sealed.open::<LocalBackPack>(&stranger).map(|_| ()));
+        let mut flipped = sealed.clone();
+        flipped.ct[10] ^= 1;
+        assert_eq!(Err(SealError::Tampered), flipped.open::<LocalBackPack>(&verifier).map(|_| ()));
+        let mut swapped = sealed.clone();
+        swapped.epk = stranger.public_key();
+        assert!(!swapped.verify::<LocalBackPack, _>(&verifier, &crs));
+        assert!(Sealed::seal(&orb(100), &[0u8; 32]).is_err());
+    }
+}
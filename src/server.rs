@@ -0,0 +1,637 @@
+use zksnark::groth16::fr::{FrLocal, G1Local, G2Local, GtLocal};
+use crate::{
+    common::CommonReference,
+    cosign::Policy,
+    interface::{BackPack, MarkZero, MarkFresh, MarkTrusted},
+    replay::{NonceStore, Window},
+    report::{Reason, VerificationReport},
+    trust::TrustPolicy,
+};
+use serde_derive::{Serialize, Deserialize};
+use std::{
+    fs::{read_dir, read_to_string},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+pub type LocalReference = CommonReference<FrLocal, G1Local, G2Local>;
+pub type LocalBackPack = BackPack<LocalReference, FrLocal, G1Local, G2Local, GtLocal>;
+
+// the largest BackPack taken in one request or line, and the longest an HTTP request line or header may be.
+pub const MAX_BODY: usize = 1 << 24;
+const MAX_HEADER: usize = 8192;
+// connections are answered by a fixed number of workers. one idle for IDLE is dropped, and any one
+// still open DEADLINE after it was accepted, so that neither a silent client nor one trickling bytes
+// holds its worker for long.
+pub const WORKERS: usize = 8;
+pub const IDLE: Duration = Duration::from_secs(10);
+pub const DEADLINE: Duration = Duration::from_secs(60);
+
+// the answer to a single request: the name of the trusted crs the orb was checked against and its report,
+// or an error when the request could not be read as a BackPack at all.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Verdict {
+    pub crs: Option<String>,
+    pub report: Option<VerificationReport>,
+    pub error: Option<String>,
+}
+
+// holds the trusted CommonReferences by name, keyed on their json so an orb's crs can be matched exactly.
+pub struct Verifier {
+    trusted: Vec<(String, String)>,
+    replay: Option<Mutex<Box<dyn NonceStore + Send>>>,
+    window: Window,
+    trust: Option<TrustPolicy>,
+    approval: Option<Policy>,
+    workers: usize,
+    idle: Duration,
+    deadline: Duration,
+}
+
+impl Verifier {
+    pub fn into(trusted: Vec<(String, LocalReference)>) -> Self {
+        Verifier {
+            trusted: trusted.into_iter()
+                .map(|(name, crs)| (
+                    name,
+                    serde_json::to_string(&crs).expect("Verifier::into() serializing a trusted CommonReference")
+                ))
+                .collect(),
+            replay: None,
+            window: Window::default(),
+            trust: None,
+            approval: None,
+            workers: WORKERS,
+            idle: IDLE,
+            deadline: DEADLINE,
+        }
+    }
+
+    // every file in the directory is read as a CommonReference and named after its file stem.
+    pub fn load<P: AsRef<Path>>(dir: P) -> std::io::Result<Self> {
+        let mut trusted = Vec::new();
+        for entry in read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() {
+                let crs: LocalReference = serde_json::from_str(&read_to_string(&path)?)
+                    .map_err(|e| std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Verifier::load() parsing {}: {}", path.display(), e)
+                    ))?;
+                let name = path.file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                trusted.push((name, crs));
+            }
+        }
+        trusted.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Self::into(trusted))
+    }
+
+    // turns on replay protection, shared by every connection.
+    pub fn with_replay<S: NonceStore + Send + 'static>(mut self, store: S, window: Window) -> Self {
+        self.replay = Some(Mutex::new(Box::new(store)));
+        self.window = window;
+        self
+    }
+
+    // the window issue times must fall in, for replay protection and for the trust policy alike.
+    pub fn with_window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    // only accepts orbs signed by keys the policy trusts at their issue time, which must fall within the window.
+    pub fn with_trust(mut self, policy: TrustPolicy) -> Self {
+        self.trust = Some(policy);
+        self
+    }
+
+    // only accepts orbs asking for exactly this co-signing policy, and co-signed to its threshold.
+    pub fn with_approval(mut self, policy: Policy) -> Self {
+        self.approval = Some(policy);
+        self
+    }
+
+    // the number of connections each of serve_http and serve_unix answers at once.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = std::cmp::max(workers, 1);
+        self
+    }
+
+    // how long a connection may sit idle, and how long it may stay open at all.
+    pub fn with_timeouts(mut self, idle: Duration, deadline: Duration) -> Self {
+        self.idle = std::cmp::max(idle, Duration::from_millis(1));
+        self.deadline = std::cmp::max(deadline, Duration::from_millis(1));
+        self
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.trusted.iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    pub fn check(&self, body: &[u8]) -> Verdict {
+        let orb: LocalBackPack = match serde_json::from_slice(body) {
+            Ok(orb) => orb,
+            Err(e) => return Verdict {
+                crs: None,
+                report: None,
+                error: Some(format!("the request is not a BackPack: {}", e)),
+            },
+        };
+        let crs = serde_json::to_string(&orb.crs).expect("Verifier::check() serializing the BackPack crs");
+        let name = self.trusted.iter()
+            .find(|(_, trusted)| *trusted == crs)
+            .map(|(name, _)| name.clone());
+        // checked ahead of the store, so an orb asking for the wrong policy cannot burn its nonce.
+        let unapproved = self.approval.as_ref().and_then(|policy| orb.requires(policy).err());
+        let report = match (&name, unapproved, &self.replay, &self.trust) {
+            (None, _, _, _) => VerificationReport::untrusted(Some(String::from("the BackPack does not carry a trusted crs"))),
+            (Some(_), Some(msg), _, _) => VerificationReport::unapproved(Some(msg)),
+            (Some(_), None, Some(store), trust) => {
+                let mut store = Locked(store);
+                match trust {
+                    Some(policy) => orb.report_trusted_fresh(policy, &mut store, &self.window, crate::replay::now()),
+                    None => orb.report_fresh(&mut store, &self.window, crate::replay::now()),
+                }
+            },
+            (Some(_), None, None, Some(policy)) => orb.report_trusted(policy, &self.window, crate::replay::now()),
+            (Some(_), None, None, None) => orb.report(),
+        };
+        // an orb that could not be recorded was not judged, so the verdict is an error as well as a report.
+        let error = match report.reason {
+            Reason::StoreFailed => report.detail.clone(),
+            _ => None,
+        };
+        Verdict {
+            crs: name,
+            report: Some(report),
+            error: error,
+        }
+    }
+}
+
+// the shared store, locked for each insert or prune alone so that orbs are verified concurrently.
+// a lock poisoned by another connection fails the store rather than the server.
+struct Locked<'a>(&'a Mutex<Box<dyn NonceStore + Send>>);
+
+impl<'a> Locked<'a> {
+    fn lock(&self) -> io::Result<std::sync::MutexGuard<'a, Box<dyn NonceStore + Send>>> {
+        self.0.lock().map_err(|_| io::Error::new(io::ErrorKind::Other, "the nonce store lock was poisoned"))
+    }
+}
+
+impl<'a> NonceStore for Locked<'a> {
+    fn insert(&mut self, nonce: &[u8], iat: u64) -> io::Result<bool> {
+        self.lock()?.insert(nonce, iat)
+    }
+    fn prune(&mut self, before: u64) -> io::Result<()> {
+        self.lock()?.prune(before)
+    }
+}
+
+// the timeouts a connection's reads and writes are held to.
+trait Timeout {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+impl Timeout for TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        TcpStream::set_write_timeout(self, timeout)
+    }
+}
+
+#[cfg(unix)]
+impl Timeout for UnixStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_write_timeout(self, timeout)
+    }
+}
+
+// reads a connection, each read waiting no longer than idle and none running past the deadline.
+struct Deadline<S> {
+    stream: S,
+    idle: Duration,
+    until: Instant,
+}
+
+impl<S: Timeout> Deadline<S> {
+    fn into(stream: S, verifier: &Verifier) -> io::Result<Self> {
+        stream.set_write_timeout(Some(verifier.idle))?;
+        Ok(Deadline {
+            stream: stream,
+            idle: verifier.idle,
+            until: Instant::now() + verifier.deadline,
+        })
+    }
+}
+
+impl<S: Read + Timeout> Read for Deadline<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.until.saturating_duration_since(Instant::now());
+        if left == Duration::from_secs(0) {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "the connection was open past its deadline"))
+        }
+        self.stream.set_read_timeout(Some(std::cmp::min(self.idle, left)))?;
+        self.stream.read(buf)
+    }
+}
+
+// runs accept on the listener and on workers - 1 clones of it, each thread answering its connections in turn,
+// so a flood of connections waits in the backlog rather than spawning a thread apiece.
+fn pool<L, C, F>(workers: usize, listener: L, clone: C, accept: F)
+where
+    L: Send + 'static,
+    C: Fn(&L) -> io::Result<L>,
+    F: Fn(L) + Send + Sync + 'static,
+{
+    let accept = Arc::new(accept);
+    let handles = (1..workers)
+        .filter_map(|_| clone(&listener).ok())
+        .map(|listener| {
+            let accept = accept.clone();
+            thread::spawn(move || accept(listener))
+        })
+        .collect::<Vec<_>>();
+    accept(listener);
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+// one BackPack per line in, one Verdict per line out, for as long as the client keeps the socket open.
+#[cfg(unix)]
+pub fn serve_unix(verifier: Arc<Verifier>, listener: UnixListener) {
+    pool(verifier.workers, listener, UnixListener::try_clone, move |listener: UnixListener| {
+        for stream in listener.incoming().flatten() {
+            handle_unix(&verifier, stream);
+        }
+    });
+}
+
+// a line longer than MAX_BODY is answered with an error and the connection closed.
+#[cfg(unix)]
+fn handle_unix(verifier: &Verifier, stream: UnixStream) {
+    let (mut writer, stream) = match stream.try_clone().and_then(|writer| Ok((writer, Deadline::into(stream, verifier)?))) {
+        Ok(pair) => pair,
+        Err(_) => return,
+    };
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut line = Vec::new();
+        match reader.by_ref().take(MAX_BODY as u64 + 1).read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {},
+        }
+        let verdict = match line.len() > MAX_BODY {
+            true => Verdict {
+                crs: None,
+                report: None,
+                error: Some(format!("the request is longer than {} bytes", MAX_BODY)),
+            },
+            false if line.iter().all(u8::is_ascii_whitespace) => continue,
+            false => verifier.check(&line),
+        };
+        let text = serde_json::to_string(&verdict).expect("server::handle_unix() serializing the Verdict");
+        if writeln!(writer, "{}", text).is_err() || line.len() > MAX_BODY {
+            return
+        }
+    }
+}
+
+// a deliberately small HTTP/1.1 endpoint for localhost: POST /verify with a BackPack as the body,
+// GET /health for the names of the trusted crs values. one request per connection.
+pub fn serve_http(verifier: Arc<Verifier>, listener: TcpListener) {
+    pool(verifier.workers, listener, TcpListener::try_clone, move |listener: TcpListener| {
+        for stream in listener.incoming().flatten() {
+            handle_http(&verifier, stream);
+        }
+    });
+}
+
+// reads a line of at most MAX_HEADER bytes, None when the connection fails or the line runs on.
+fn header_line<R: BufRead>(reader: &mut R) -> Option<String> {
+    let mut line = String::new();
+    match reader.take(MAX_HEADER as u64).read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) if !line.ends_with('\n') => None,
+        Ok(_) => Some(line),
+    }
+}
+
+fn handle_http(verifier: &Verifier, stream: TcpStream) {
+    let (mut writer, stream) = match stream.try_clone().and_then(|writer| Ok((writer, Deadline::into(stream, verifier)?))) {
+        Ok(pair) => pair,
+        Err(_) => return,
+    };
+    let respond = |writer: &mut TcpStream, status: &str, response: String| {
+        let _ = write!(
+            writer,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status, response.len(), response
+        );
+    };
+    let error = |msg: String| serde_json::json!({ "error": msg }).to_string();
+    let mut reader = BufReader::new(stream);
+    let request_line = match header_line(&mut reader) {
+        Some(line) => line,
+        None => return,
+    };
+    let mut length = 0;
+    loop {
+        let header = match header_line(&mut reader) {
+            Some(header) => header,
+            None => return respond(&mut writer, "400 Bad Request", error(String::from("the headers could not be read"))),
+        };
+        let header = header.trim();
+        if header.is_empty() {
+            break
+        }
+        let mut parts = header.splitn(2, ':');
+        if let (Some(name), Some(value)) = (parts.next(), parts.next()) {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = match value.trim().parse::<usize>() {
+                    Ok(length) if length > MAX_BODY => return respond(
+                        &mut writer,
+                        "413 Payload Too Large",
+                        error(format!("the body is longer than {} bytes", MAX_BODY))
+                    ),
+                    Ok(length) => length,
+                    Err(_) => return respond(&mut writer, "400 Bad Request", error(String::from("the Content-Length is not a number"))),
+                };
+            }
+        }
+    }
+    let mut body = vec![0; length];
+    if reader.read_exact(&mut body).is_err() {
+        return
+    }
+    let mut words = request_line.split_whitespace();
+    let (status, response) = match (words.next(), words.next()) {
+        (Some("POST"), Some("/verify")) => {
+            let verdict = verifier.check(&body);
+            let status = match (&verdict.report, &verdict.error) {
+                (Some(report), Some(_)) if report.reason == Reason::StoreFailed => "503 Service Unavailable",
+                (_, Some(_)) => "400 Bad Request",
+                (_, None) => "200 OK",
+            };
+            (status, serde_json::to_string(&verdict).expect("server::handle_http() serializing the Verdict"))
+        },
+        (Some("GET"), Some("/health")) => (
+            "200 OK",
+            serde_json::json!({ "status": "ok", "trusted": verifier.names() }).to_string()
+        ),
+        (_, _) => ("404 Not Found", error(String::from("not found"))),
+    };
+    respond(&mut writer, status, response);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        server::{Verifier, Verdict, LocalReference, MAX_BODY, serve_http},
+        common::{CommonReference, Common},
+        cosign::{Policy, Signer},
+        crypto::{EdDSA, SignatureScheme},
+        replay::{MemoryStore, NonceStore, Window},
+        report::Reason,
+        tests::sample_orb,
+    };
+    use std::{
+        io::{self, BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    fn orb(crs: &LocalReference, out: usize) -> String {
+        serde_json::to_string(&sample_orb(crs, out, EdDSA::<String>::init_key_pair())).unwrap()
+    }
+
+    fn setup() -> (LocalReference, LocalReference, Arc<Verifier>) {
+        let dir = std::env::temp_dir().join(format!("zero_orb_server_{}_{:?}", std::process::id(), thread::current().id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let trusted: LocalReference = CommonReference::new(String::from("(in a b) (out x) (verify x) (program (= x (* a b)))"));
+        let other: LocalReference = CommonReference::new(String::from("(in a b) (out x) (verify x) (program (= x (* a a)))"));
+        std::fs::write(dir.join("mul.crs"), serde_json::to_string(&trusted).unwrap()).unwrap();
+        let verifier = Verifier::load(&dir).unwrap().with_replay(MemoryStore::new(), Window::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+        (trusted, other, Arc::new(verifier))
+    }
+
+    fn reason(verdict: &Verdict) -> Reason {
+        verdict.report.as_ref().unwrap().reason
+    }
+
+    #[test]
+    fn test_http() {
+        let (trusted, other, verifier) = setup();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve_http(verifier, listener));
+
+        let post = move |body: &str| -> (String, Verdict) {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(
+                stream,
+                "POST /verify HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(), body
+            ).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            let status = response.lines().next().unwrap().to_string();
+            let body = response.split("\r\n\r\n").nth(1).unwrap();
+            (status, serde_json::from_str(body).unwrap())
+        };
+
+        // requests are answered concurrently.
+        let good = orb(&trusted, 100);
+        let handles = (0..4)
+            .map(|i| {
+                let body = match i {
+                    0 => good.clone(),
+                    _ => orb(&trusted, 100),
+                };
+                thread::spawn(move || post(&body))
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            let (status, verdict) = handle.join().unwrap();
+            assert_eq!("HTTP/1.1 200 OK", status);
+            assert_eq!(Some(String::from("mul")), verdict.crs);
+            assert_eq!(Reason::Valid, reason(&verdict));
+        }
+        assert_eq!(Reason::Replayed, reason(&post(&good).1));
+        assert_eq!(Reason::BadProof, reason(&post(&orb(&trusted, 90)).1));
+        assert_eq!(Reason::UntrustedCrs, reason(&post(&orb(&other, 100)).1));
+        let (status, verdict) = post("{\"not\": \"an orb\"}");
+        assert_eq!("HTTP/1.1 400 Bad Request", status);
+        assert!(verdict.error.is_some());
+
+        let raw = |request: String| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "{}", request).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        let response = raw(String::from("GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n"));
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\"mul\""));
+
+        // bodies are capped before they are read, and a length that is not a number is refused rather than read as 0.
+        let response = raw(format!("POST /verify HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY + 1));
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large"));
+        let response = raw(String::from("POST /verify HTTP/1.1\r\nContent-Length: lots\r\n\r\n{}"));
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+        let response = raw(format!("POST /verify HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "x".repeat(10_000)));
+        assert!(response.starts_with("HTTP/1.1 400 Bad Request"));
+    }
+
+    #[test]
+    fn test_approval() {
+        // an orb that does not ask for the demanded policy is Unapproved without its nonce being recorded.
+        let (trusted, _, _) = setup();
+        let auditor = Signer::of_key(&EdDSA::<String>::init_key_pair()).unwrap();
+        let verifier = Verifier::into(vec![(String::from("mul"), trusted.clone())])
+            .with_replay(MemoryStore::new(), Window::default());
+        let demanding = Verifier::into(vec![(String::from("mul"), trusted.clone())])
+            .with_replay(MemoryStore::new(), Window::default())
+            .with_approval(Policy::into(1, vec![auditor]).unwrap());
+        let good = orb(&trusted, 100);
+        let verdict = demanding.check(good.as_bytes());
+        assert_eq!(Reason::Unapproved, reason(&verdict));
+        assert_eq!(Some(String::from("the orb does not ask for co-signatures")), verdict.report.unwrap().detail);
+        assert_eq!(Reason::Unapproved, reason(&demanding.check(good.as_bytes())));
+        assert_eq!(Reason::Valid, reason(&verifier.check(good.as_bytes())));
+    }
+
+    // fails every write, as a full disk would.
+    struct Full;
+
+    impl NonceStore for Full {
+        fn insert(&mut self, _: &[u8], _: u64) -> io::Result<bool> {
+            Err(io::Error::new(io::ErrorKind::Other, "no space left on device"))
+        }
+        fn prune(&mut self, _: u64) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_store_failure() {
+        // a store that cannot record the nonce makes the verdict an error and the answer a 503, and the
+        // server carries on answering on its one worker.
+        let (trusted, _, _) = setup();
+        let verifier = Verifier::into(vec![(String::from("mul"), trusted.clone())])
+            .with_replay(Full, Window::default())
+            .with_workers(1);
+        let verdict = verifier.check(orb(&trusted, 100).as_bytes());
+        assert_eq!(Reason::StoreFailed, reason(&verdict));
+        assert!(verdict.error.unwrap().contains("no space left"));
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve_http(Arc::new(verifier), listener));
+        for _ in 0..2 {
+            let body = orb(&trusted, 100);
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "POST /verify HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            assert!(response.starts_with("HTTP/1.1 503 Service Unavailable"));
+        }
+    }
+
+    #[test]
+    fn test_idle_clients() {
+        // clients that hold every worker, some silent and some trickling a byte at a time, are dropped
+        // and the next client still gets its verdict.
+        let (trusted, _, _) = setup();
+        let verifier = Verifier::into(vec![(String::from("mul"), trusted.clone())])
+            .with_timeouts(Duration::from_millis(300), Duration::from_secs(1));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve_http(Arc::new(verifier), listener));
+
+        let silent = (0..4).map(|_| TcpStream::connect(addr).unwrap()).collect::<Vec<_>>();
+        let trickling = (0..4)
+            .map(|_| {
+                let mut stream = TcpStream::connect(addr).unwrap();
+                thread::spawn(move || {
+                    for byte in b"POST /verify HTTP/1.1\r\nX-Padding: ".iter().chain([b'x'; 64].iter()) {
+                        if stream.write_all(&[*byte]).is_err() {
+                            return
+                        }
+                        thread::sleep(Duration::from_millis(100));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        let body = orb(&trusted, 100);
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "POST /verify HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        let verdict: Verdict = serde_json::from_str(response.split("\r\n\r\n").nth(1).unwrap()).unwrap();
+        assert_eq!(Reason::Valid, reason(&verdict));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        drop(silent);
+        for handle in trickling {
+            handle.join().unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix() {
+        use crate::server::serve_unix;
+        use std::os::unix::net::{UnixListener, UnixStream};
+
+        let (trusted, _, verifier) = setup();
+        let path = std::env::temp_dir().join(format!("zero_orb_{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        thread::spawn(move || serve_unix(verifier, listener));
+
+        let stream = UnixStream::connect(&path).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let mut ask = |body: String| -> Verdict {
+            writeln!(writer, "{}", body).unwrap();
+            serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap()
+        };
+        let good = orb(&trusted, 100);
+        assert_eq!(Reason::Valid, reason(&ask(good.clone())));
+        assert_eq!(Reason::Replayed, reason(&ask(good)));
+        assert_eq!(Reason::BadProof, reason(&ask(orb(&trusted, 90))));
+        assert!(ask(String::from("[]")).error.is_some());
+        // an overlong line is answered with an error and the connection closed.
+        let _ = writeln!(writer, "{}", " ".repeat(MAX_BODY + 1));
+        let verdict: Verdict = serde_json::from_str(&lines.next().unwrap().unwrap()).unwrap();
+        assert!(verdict.error.is_some());
+        assert!(lines.next().is_none());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
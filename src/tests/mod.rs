@@ -0,0 +1,37 @@
+use zksnark::groth16::fr::{FrLocal, G1Local, G2Local, GtLocal};
+use std::fs::read_to_string;
+use crate::{
+    common::{CommonReference, Common},
+    interface::{Andromeda, BackPack, GoZero},
+    knowledge::Knowledge,
+};
+
+// fixtures shared by the unit tests, read from the files beside this module.
+
+pub type SampleReference = CommonReference<FrLocal, G1Local, G2Local>;
+pub type SampleOrb = BackPack<SampleReference, FrLocal, G1Local, G2Local, GtLocal>;
+
+// the crs of x = a * b, as written by common::write_test_crs.
+pub fn sample_crs() -> SampleReference {
+    CommonReference::read(
+        &read_to_string("src/tests/files/crs/sample.crs").expect("tests::sample_crs() reading the sample crs")
+    )
+}
+
+// the prover of 20 * 5 = out under any crs of x = a * b, signed with key_pair, for a test to add to before go.
+pub fn sample_prover<K: Into<Box<[u8]>>>(
+    crs: &SampleReference,
+    out: usize,
+    key_pair: K,
+) -> Andromeda<SampleReference, Knowledge, FrLocal, G1Local, G2Local, GtLocal> {
+    Andromeda::into(
+        crs.clone(),
+        Knowledge::into_num_only(Some(vec![20, 5]), None),
+        Some(vec![out]),
+        key_pair
+    )
+}
+
+pub fn sample_orb<K: Into<Box<[u8]>>>(crs: &SampleReference, out: usize, key_pair: K) -> SampleOrb {
+    sample_prover(crs, out, key_pair).go()
+}
@@ -11,6 +11,29 @@ pub trait IntoField {
         U: Field + From<usize>;
 }
 
+// the number of bits each value is expanded into for a given tag.
+// the i tags are signed, their values held in two's complement: -1 under "i8" is 255.
+pub fn width(t: &str) -> Option<usize> {
+    match t {
+        "u8" | "i8" => Some(8),
+        "u16" | "i16" => Some(16),
+        "u32" | "i32" => Some(32),
+        "u64" | "i64" => Some(64),
+        _ => None,
+    }
+}
+
+// x in two's complement at the width of t, as the usize Knowledge and Marker expand into its bits,
+// or None when x does not fit in a signed value of that width.
+pub fn twos_complement(x: isize, t: &str) -> Option<usize> {
+    let width = width(t)?;
+    let (x, half) = (x as i128, 1i128 << (width - 1));
+    match x < -half || x >= half {
+        true => None,
+        false => Some((x & ((half << 1) - 1)) as usize),
+    }
+}
+
 // S: PrimInt lets the impl function across u8 -> u64 generically.
 // matching the length of the vec and returning a None is currently used to process empty values in the Knowledge struct.
 // 'static str solution in fn collect_bits() is required for determining whether a number should be converted to 64, 32 etc bits.
@@ -33,27 +56,8 @@ impl IntoField for Vec<usize> {
     {
         let x = self.into_iter()
             .map(|mut n| {
-                let mut bits: Vec<usize> = Vec::new(); 
-                let len: usize;
-                match t.as_ref() {
-                    "u8" => { 
-                        bits.extend_from_slice(&[0; 8]); 
-                        len = 8; 
-                    },
-                    "u16" => { 
-                        bits.extend_from_slice(&[0; 16]); 
-                        len = 16; 
-                    },
-                    "u32" => { 
-                        bits.extend_from_slice(&[0; 32]); 
-                        len = 32; 
-                    },
-                    "u64" => { 
-                        bits.extend_from_slice(&[0; 64]); 
-                        len = 64; 
-                    },
-                    _ => panic!("unexpected String in t.as_ref() IntoField::collect_bits()"),
-                };
+                let len = width(t).expect("unexpected String in t.as_ref() IntoField::collect_bits()");
+                let mut bits: Vec<usize> = vec![0; len];
                 for i in 0..len {
                     bits[i] = n % 2;
                     n = n >> 1;
@@ -67,4 +71,29 @@ impl IntoField for Vec<usize> {
             }).collect::<Vec<U>>()
         }).concat()
     }
-}
\ No newline at end of file
+}
+
+// the signed encoding: nums are the values themselves, negative ones as their additive inverse in the field,
+// and bits are the two's complement of each value at the width of the tag, least significant first.
+impl IntoField for Vec<isize> {
+    fn collect_nums<T>(self) -> Vec<T>
+    where
+        T: Field + From<usize>
+    {
+        self.into_iter()
+            .map(|n| match n < 0 {
+                true => -T::from(n.wrapping_neg() as usize),
+                false => T::from(n as usize),
+            })
+            .collect::<Vec<T>>()
+    }
+    fn collect_bits<U>(self, t: &String) -> Vec<U>
+    where
+        U: Field + From<usize>
+    {
+        self.into_iter()
+            .map(|n| twos_complement(n, t).expect("IntoField::collect_bits() a signed value out of range for its tag"))
+            .collect::<Vec<usize>>()
+            .collect_bits(t)
+    }
+}
@@ -60,4 +60,23 @@ fn test_collect_bits() {
     ];
     assert_eq!(y_64.len(), 64);
     assert!(x_64.collect_bits::<FrLocal>(&"u64".to_string()) == y_64);
-}
\ No newline at end of file
+}
+#[test]
+fn test_collect_signed() {
+    use crate::transform::into_field::{IntoField, twos_complement};
+    use zksnark::groth16::fr::FrLocal;
+
+    let x: Vec<isize> = vec![-3, 7];
+    let y = vec![
+        -FrLocal::from(3), FrLocal::from(7)
+    ];
+    assert!(x.clone().collect_nums::<FrLocal>() == y);
+    assert!(x.collect_bits::<FrLocal>(&"i8".to_string()) == vec![253usize, 7].collect_bits::<FrLocal>(&"u8".to_string()));
+
+    assert_eq!(Some(128), twos_complement(-128, "i8"));
+    assert_eq!(Some(127), twos_complement(127, "i8"));
+    assert_eq!(Some(0xffff), twos_complement(-1, "i16"));
+    assert_eq!(None, twos_complement(128, "i8"));
+    assert_eq!(None, twos_complement(-129, "i8"));
+    assert_eq!(None, twos_complement(1, "s8"));
+}
@@ -0,0 +1,253 @@
+use serde_derive::{Serialize, Deserialize};
+use std::{
+    fmt,
+    fs::read_to_string,
+    io,
+    path::Path,
+};
+use crate::{
+    crypto::{
+        Algorithm,
+        cert::{self, Certificate, CertError},
+    },
+    report::Reason,
+};
+
+// a signing key the verifier accepts orbs from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrustedKey {
+    pub alg: Algorithm,
+    pub puk: Box<[u8]>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+// orbs issued at or after since are refused. since = 0 refuses the key outright.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Revocation {
+    pub puk: Box<[u8]>,
+    pub since: u64,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+// from hands over to to at the given time: orbs issued from then on must be signed by to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rotation {
+    pub from: Box<[u8]>,
+    pub to: TrustedKey,
+    pub at: u64,
+}
+
+// the verifier's view of which keys may sign, kept as a json file beside the trusted crs values.
+// besides the keys it lists, it trusts any key an orb proves with a certificate chain up to one of its roots.
+// times are unix seconds compared against the orb's iat. iat is chosen by the prover, so a stolen key
+// could backdate its orbs past a revocation; the freshness window the orb is checked in first is what bounds that.
+// certificates are the issuer's word rather than the prover's, and are checked at the verifier's own time.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TrustPolicy {
+    #[serde(default)]
+    pub allowed: Vec<TrustedKey>,
+    #[serde(default)]
+    pub revoked: Vec<Revocation>,
+    #[serde(default)]
+    pub rotations: Vec<Rotation>,
+    #[serde(default)]
+    pub roots: Vec<Certificate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distrust {
+    Unknown,
+    Revoked(u64, Option<String>),
+    Rotated(u64),
+    NotYetValid(u64),
+    Certificate(CertError),
+}
+
+impl Distrust {
+    pub fn reason(&self) -> Reason {
+        match self {
+            Distrust::Unknown | Distrust::NotYetValid(_) => Reason::UnknownKey,
+            Distrust::Revoked(_, _) => Reason::RevokedKey,
+            Distrust::Rotated(_) => Reason::RotatedKey,
+            Distrust::Certificate(_) => Reason::BadCertificate,
+        }
+    }
+}
+
+impl fmt::Display for Distrust {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Distrust::Unknown => write!(f, "the signing key is not trusted"),
+            Distrust::Revoked(since, Some(reason)) => write!(f, "the signing key was revoked at {}: {}", since, reason),
+            Distrust::Revoked(since, None) => write!(f, "the signing key was revoked at {}", since),
+            Distrust::Rotated(at) => write!(f, "the signing key was rotated out at {}", at),
+            Distrust::NotYetValid(at) => write!(f, "the signing key is only trusted from {}", at),
+            Distrust::Certificate(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl TrustPolicy {
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        serde_json::from_str(&read_to_string(&path)?)
+            .map_err(|e| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("TrustPolicy::read() parsing {}: {}", path.as_ref().display(), e)
+            ))
+    }
+
+    // whether an orb signed by puk under alg and issued at iat comes from a key trusted at that time.
+    pub fn check(&self, alg: Algorithm, puk: &[u8], iat: u64) -> Result<(), Distrust> {
+        if let Some(revocation) = self.revoked.iter().find(|r| &*r.puk == puk && iat >= r.since) {
+            return Err(Distrust::Revoked(revocation.since, revocation.reason.clone()))
+        }
+        match self.introduced(Some(alg), puk, self.rotations.len()) {
+            Some(from) if iat < from => return Err(Distrust::NotYetValid(from)),
+            Some(_) => (),
+            None => return Err(Distrust::Unknown),
+        }
+        match self.rotations.iter().filter(|r| &*r.from == puk && r.at <= iat).map(|r| r.at).min() {
+            Some(at) => Err(Distrust::Rotated(at)),
+            None => Ok(()),
+        }
+    }
+
+    // as check, but an orb carrying a certificate chain is judged by the chain when there are roots to check it against.
+    // the chain must certify this very key and be valid now, no key along it may be revoked at iat,
+    // and the key must not have been rotated out by iat. returns the certified subject.
+    pub fn check_certified(
+        &self, 
+        alg: Algorithm, 
+        puk: &[u8], 
+        iat: u64, 
+        now: u64, 
+        chain: Option<&[Certificate]>
+    ) -> Result<Option<String>, Distrust> {
+        let chain = match chain {
+            Some(chain) if !self.roots.is_empty() => chain,
+            _ => return self.check(alg, puk, iat).map(|_| None),
+        };
+        let leaf = cert::verify_chain(chain, &self.roots, now).map_err(Distrust::Certificate)?;
+        if leaf.alg != alg || &*leaf.puk != puk {
+            return Err(Distrust::Certificate(CertError::Broken(leaf.subject.clone())))
+        }
+        let keys = chain.iter().map(|cert| &cert.puk).chain(self.roots.iter().map(|root| &root.puk));
+        for key in keys {
+            if let Some(revocation) = self.revoked.iter().find(|r| r.puk == *key && iat >= r.since) {
+                return Err(Distrust::Revoked(revocation.since, revocation.reason.clone()))
+            }
+        }
+        match self.rotations.iter().filter(|r| &*r.from == puk && r.at <= iat).map(|r| r.at).min() {
+            Some(at) => Err(Distrust::Rotated(at)),
+            None => Ok(Some(leaf.subject.clone())),
+        }
+    }
+
+    // the earliest time the key became trusted, either listed outright or rotated into by a key
+    // that was itself trusted and not revoked when it handed over. alg is None for the handing over key,
+    // which a rotation names by its public key alone. depth stops rotation cycles.
+    fn introduced(&self, alg: Option<Algorithm>, puk: &[u8], depth: usize) -> Option<u64> {
+        let named = |key: &TrustedKey| alg.map_or(true, |alg| key.alg == alg) && &*key.puk == puk;
+        if self.allowed.iter().any(named) {
+            return Some(0)
+        }
+        if depth == 0 {
+            return None
+        }
+        self.rotations.iter()
+            .filter(|r| named(&r.to))
+            .filter(|r| {
+                let revoked = self.revoked.iter().any(|revocation| revocation.puk == r.from && revocation.since <= r.at);
+                match self.introduced(None, &r.from, depth - 1) {
+                    Some(from) => from <= r.at && !revoked,
+                    None => false,
+                }
+            })
+            .map(|r| r.at)
+            .min()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::{
+        crypto::{Algorithm, cert::Certificate, keystore::KeyHandle},
+        report::Reason,
+        trust::{TrustPolicy, TrustedKey, Revocation, Rotation, Distrust},
+    };
+
+    #[test]
+    fn test_trust_policy() {
+        let puk = |alg: Algorithm| alg.public_key(&alg.init_key_pair());
+        let (old, new, newer, stranger) = (
+            puk(Algorithm::Ed25519), puk(Algorithm::EcdsaP256), puk(Algorithm::Ed25519), puk(Algorithm::Ed25519)
+        );
+        let key = |alg: Algorithm, puk: &Box<[u8]>| TrustedKey { alg: alg, puk: puk.clone(), name: None };
+        let mut policy = TrustPolicy {
+            allowed: vec![key(Algorithm::Ed25519, &old)],
+            revoked: vec![],
+            roots: vec![],
+            rotations: vec![
+                Rotation { from: old.clone(), to: key(Algorithm::EcdsaP256, &new), at: 100 },
+                Rotation { from: new.clone(), to: key(Algorithm::Ed25519, &newer), at: 200 },
+            ],
+        };
+
+        assert_eq!(Ok(()), policy.check(Algorithm::Ed25519, &old, 99));
+        assert_eq!(Err(Distrust::Rotated(100)), policy.check(Algorithm::Ed25519, &old, 100));
+        assert_eq!(Err(Distrust::NotYetValid(100)), policy.check(Algorithm::EcdsaP256, &new, 99));
+        assert_eq!(Ok(()), policy.check(Algorithm::EcdsaP256, &new, 150));
+        assert_eq!(Err(Distrust::Rotated(200)), policy.check(Algorithm::EcdsaP256, &new, 250));
+        assert_eq!(Ok(()), policy.check(Algorithm::Ed25519, &newer, 250));
+        // the key has to be presented under the scheme it was trusted with.
+        assert_eq!(Err(Distrust::Unknown), policy.check(Algorithm::EcdsaP384, &new, 150));
+        assert_eq!(Err(Distrust::Unknown), policy.check(Algorithm::Ed25519, &stranger, 0));
+        assert_eq!(Reason::UnknownKey, Distrust::NotYetValid(100).reason());
+
+        // revoking the middle key also voids the hand over it made afterwards.
+        policy.revoked.push(Revocation { puk: new.clone(), since: 180, reason: Some(String::from("lost")) });
+        assert_eq!(Ok(()), policy.check(Algorithm::EcdsaP256, &new, 150));
+        assert_eq!(Err(Distrust::Revoked(180, Some(String::from("lost")))), policy.check(Algorithm::EcdsaP256, &new, 190));
+        assert_eq!(Err(Distrust::Unknown), policy.check(Algorithm::Ed25519, &newer, 250));
+
+        // a rotation cycle does not recurse forever.
+        policy.rotations.push(Rotation { from: stranger.clone(), to: key(Algorithm::Ed25519, &stranger), at: 0 });
+        assert_eq!(Err(Distrust::Unknown), policy.check(Algorithm::Ed25519, &stranger, 10));
+
+        let json = serde_json::to_string(&policy).unwrap();
+        assert_eq!(policy, serde_json::from_str::<TrustPolicy>(&json).unwrap());
+        assert_eq!(TrustPolicy::default(), serde_json::from_str::<TrustPolicy>("{}").unwrap());
+    }
+
+    #[test]
+    fn test_certified_rotation() {
+        let (mut root_key, leaf_key) = (
+            KeyHandle::generate("root", Algorithm::Ed25519),
+            KeyHandle::generate("leaf", Algorithm::EcdsaP256),
+        );
+        let cert = |subject: &str, key: &KeyHandle, ca: bool| Certificate::into(
+            subject, key.algorithm(), key.public_key(), BTreeMap::new(), 0, 1000, ca
+        );
+        let root = cert("root", &root_key, true).sign("root", &mut root_key.clone()).unwrap();
+        let leaf = cert("dr who", &leaf_key, false).sign("root", &mut root_key).unwrap();
+        let next = Algorithm::Ed25519.public_key(&Algorithm::Ed25519.init_key_pair());
+        let policy = TrustPolicy {
+            allowed: vec![],
+            revoked: vec![],
+            roots: vec![root],
+            rotations: vec![
+                Rotation { from: leaf_key.public_key(), to: TrustedKey { alg: Algorithm::Ed25519, puk: next, name: None }, at: 100 },
+            ],
+        };
+        let chain = vec![leaf];
+        let check = |iat: u64| policy.check_certified(Algorithm::EcdsaP256, &leaf_key.public_key(), iat, 500, Some(&chain));
+
+        assert_eq!(Ok(Some(String::from("dr who"))), check(99));
+        // a valid chain does not keep a rotated out key trusted.
+        assert_eq!(Err(Distrust::Rotated(100)), check(100));
+        assert_eq!(Reason::RotatedKey, check(150).unwrap_err().reason());
+    }
+}